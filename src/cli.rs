@@ -0,0 +1,131 @@
+//! Command-line entry points for CI. Each command runs the same handler as the
+//! corresponding MCP tool, prints its JSON response to stdout, and maps the
+//! result to a process exit code.
+
+use anyhow::{Result, bail};
+use std::process::ExitCode;
+
+use crate::config::Config;
+use crate::tools::{self, SigilServer};
+
+const USAGE: &str = "\
+Usage: sigil-mcp [COMMAND]
+
+Without a command, serves MCP over stdio.
+
+Commands:
+  detect-changes --base <REF>   Report contract weakening/strengthening against a git ref.
+                                Exits 1 when any weakening change is found.
+  fmt [--check]                 Rewrite contract files in the canonical layout. With --check,
+                                only list unformatted files and exit 1 if there are any.
+  gate [--review <ID>] [--branch <NAME>] [--overrides <FILE|->]
+                                Evaluate the CI gate from validation and recorded verdicts.
+                                --overrides reads sigil-override directives from a file,
+                                or from stdin when given '-'. Exits 1 when the gate fails.
+  report --format <sarif|junit|markdown> [--review <ID>] [--output <FILE>]
+                                Export validation issues and recorded verdicts as a report,
+                                to stdout or to --output.
+  lsp                           Serve the language server protocol over stdio for editing
+                                .contract.toml files.";
+
+pub async fn run(config: Config, args: &[String]) -> Result<ExitCode> {
+    let server = SigilServer::new(config);
+    match args[0].as_str() {
+        "detect-changes" => {
+            let base_ref = required_flag(args, "--base")?;
+            let output = tools::detect_contract_changes::handle(
+                &server,
+                tools::detect_contract_changes::Params { base_ref },
+            )
+            .await;
+            Ok(finish(&output, |json| json["weakening"].as_u64().unwrap_or(0) > 0))
+        }
+        "fmt" => {
+            let check = args.iter().any(|a| a == "--check");
+            let output = tools::format_contracts::handle(
+                &server,
+                tools::format_contracts::Params { contract_id: None, check: Some(check) },
+            )
+            .await;
+            Ok(finish(&output, |json| {
+                let unformatted = check && json["changed"].as_array().is_some_and(|c| !c.is_empty());
+                unformatted || json["errors"].as_array().is_some_and(|e| !e.is_empty())
+            }))
+        }
+        "gate" => {
+            let overrides = match flag(args, "--overrides").as_deref() {
+                Some("-") => Some(std::io::read_to_string(std::io::stdin())?),
+                Some(path) => Some(std::fs::read_to_string(path)?),
+                None => None,
+            };
+            let output = tools::evaluate_gate::handle(
+                &server,
+                tools::evaluate_gate::Params {
+                    review_id: flag(args, "--review"),
+                    branch: flag(args, "--branch"),
+                    overrides,
+                    overrides_path: None,
+                },
+            )
+            .await;
+            Ok(finish(&output, |json| json["decision"] == "fail"))
+        }
+        "report" => {
+            let format = required_flag(args, "--format")?;
+            let Ok(format) = serde_json::from_value(serde_json::Value::String(format.clone())) else {
+                bail!("Unknown report format '{format}'\n\n{USAGE}");
+            };
+            let output = tools::export_report::handle(
+                &server,
+                tools::export_report::Params { format, review_id: flag(args, "--review") },
+            )
+            .await;
+            let json: serde_json::Value = serde_json::from_str(&output)?;
+            let Some(content) = json["content"].as_str() else {
+                return Ok(finish(&output, |_| false));
+            };
+            match flag(args, "--output") {
+                Some(path) => std::fs::write(path, content)?,
+                None => println!("{content}"),
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        "lsp" => {
+            let root = server.root().to_path_buf();
+            Ok(tools::lsp::run(server.config, root)?)
+        }
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        other => bail!("Unknown command '{other}'\n\n{USAGE}"),
+    }
+}
+
+/// Print a tool response and derive the exit code: 2 for an error response,
+/// 1 when `failed` holds, 0 otherwise.
+fn finish(output: &str, failed: impl Fn(&serde_json::Value) -> bool) -> ExitCode {
+    println!("{output}");
+    let json: serde_json::Value = serde_json::from_str(output).unwrap_or_default();
+    if json.get("error").is_some() {
+        ExitCode::from(2)
+    } else if failed(&json) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn flag(args: &[String], name: &str) -> Option<String> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+fn required_flag(args: &[String], name: &str) -> Result<String> {
+    match flag(args, name) {
+        Some(v) => Ok(v),
+        None => bail!("Missing required flag {name}\n\n{USAGE}"),
+    }
+}
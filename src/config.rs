@@ -1,5 +1,10 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use globset::Glob;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::model::{Priority, Status};
 
 /// Default agent instructions embedded at compile time from docs/agent-instructions.md.
 pub const DEFAULT_INSTRUCTIONS: &str =
@@ -19,6 +24,209 @@ pub struct Config {
     /// Global notes delivered to agents via sigil_get_notes.
     /// Intended for project-specific conventions the agent should know about.
     pub notes: Option<String>,
+
+    /// Directory where review sessions (recorded verdicts) are stored.
+    #[serde(default = "default_reviews_dir")]
+    pub reviews_dir: String,
+
+    /// How contract priority and status map to CI gate outcomes.
+    #[serde(default)]
+    pub gate: GateConfig,
+
+    /// Size limits for file contents returned to agents.
+    #[serde(default)]
+    pub limits: LimitsConfig,
+
+    /// Secret redaction applied to file contents returned to agents.
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+
+    /// JSON Schemas for the `[trigger]` table, keyed by trigger type. A
+    /// contract whose trigger has a registered type must match its schema;
+    /// other trigger types stay free-form.
+    #[serde(default)]
+    pub trigger_schemas: BTreeMap<String, serde_json::Value>,
+
+    /// Validators for `trigger_schemas`, compiled once by `Config::load`.
+    #[serde(skip)]
+    pub trigger_validators: BTreeMap<String, Arc<jsonschema::Validator>>,
+
+    /// Project-specific contract fields, checked alongside the built-in schema.
+    #[serde(default)]
+    pub schema: SchemaConfig,
+}
+
+/// Extension of the built-in contract schema. `extension` is the path of a
+/// JSON Schema that every contract must also match, declaring extra
+/// top-level fields (e.g. `owner`) under its `properties`. With `strict`,
+/// top-level fields declared by neither schema are validation errors.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct SchemaConfig {
+    pub extension: Option<String>,
+    pub strict: bool,
+    /// The extension schema read from `extension`, filled in by `Config::load`.
+    #[serde(skip)]
+    pub extension_schema: Option<ExtensionSchema>,
+}
+
+/// An extension schema together with its validator, compiled once when the
+/// config is loaded.
+#[derive(Debug, Clone)]
+pub struct ExtensionSchema {
+    pub schema: serde_json::Value,
+    pub validator: Arc<jsonschema::Validator>,
+}
+
+impl ExtensionSchema {
+    pub fn compile(schema: serde_json::Value) -> Result<Self> {
+        let validator = jsonschema::validator_for(&schema).map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok(Self { schema, validator: Arc::new(validator) })
+    }
+}
+
+impl SchemaConfig {
+    /// Read and compile the extension schema, if one is configured.
+    fn load_extension(&mut self) -> Result<()> {
+        let Some(path) = &self.extension else {
+            return Ok(());
+        };
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("schema.extension: failed to read '{path}'"))?;
+        let schema: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("schema.extension: '{path}' is not valid JSON"))?;
+        let extension = ExtensionSchema::compile(schema)
+            .with_context(|| format!("schema.extension: '{path}' is not valid JSON Schema"))?;
+        self.extension_schema = Some(extension);
+        Ok(())
+    }
+}
+
+/// Byte limits for file contents inlined by sigil_get_contract and
+/// sigil_review_changeset. Files over the per-file limit are truncated to a
+/// head and tail excerpt; once the per-response limit is used up, further
+/// files are omitted.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields, default)]
+pub struct LimitsConfig {
+    pub max_file_bytes: usize,
+    pub max_response_bytes: usize,
+}
+
+/// Secret redaction for file contents. The built-in detectors cover private
+/// keys, common token formats and `password = ...` style assignments;
+/// `patterns` adds project-specific regexes. A pattern with a `secret`
+/// capture group has only that group replaced, otherwise the whole match.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields, default)]
+pub struct RedactionConfig {
+    /// Whether the built-in detectors run. Custom patterns always do.
+    pub enabled: bool,
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self { enabled: true, patterns: Vec::new() }
+    }
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 64 * 1024,
+            max_response_bytes: 512 * 1024,
+        }
+    }
+}
+
+/// What a problem in a contract (validation error or failing verdict) does to the CI gate.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Enforcement {
+    /// Fails the gate.
+    Block,
+    /// Reported as a warning; the gate still passes.
+    Warn,
+    /// Reported for information only.
+    Info,
+    /// Not enforced at all.
+    Ignore,
+}
+
+/// Mapping from priority and status to enforcement. Status mappings take
+/// precedence over priority: a draft or deprecated contract uses its status
+/// mapping whatever its priority.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct GateConfig {
+    pub must: Enforcement,
+    pub should: Enforcement,
+    pub prefer: Enforcement,
+    pub draft: Enforcement,
+    pub deprecated: Enforcement,
+    /// What contract files that cannot be loaded (invalid TOML, missing or
+    /// mistyped fields) do to the gate. Such a file has no priority of its
+    /// own, so it blocks by default.
+    pub load_errors: Enforcement,
+    /// Overrides applied when the gate runs on a branch matching `pattern`.
+    /// Later entries win over earlier ones.
+    pub branches: Vec<BranchGate>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BranchGate {
+    /// Glob matched against the branch name (e.g. "release/*").
+    pub pattern: String,
+    pub must: Option<Enforcement>,
+    pub should: Option<Enforcement>,
+    pub prefer: Option<Enforcement>,
+    pub draft: Option<Enforcement>,
+    pub deprecated: Option<Enforcement>,
+}
+
+impl Default for GateConfig {
+    fn default() -> Self {
+        Self {
+            must: Enforcement::Block,
+            should: Enforcement::Warn,
+            prefer: Enforcement::Info,
+            draft: Enforcement::Ignore,
+            deprecated: Enforcement::Warn,
+            load_errors: Enforcement::Block,
+            branches: Vec::new(),
+        }
+    }
+}
+
+impl GateConfig {
+    /// Enforcement for a contract, taking branch overrides into account.
+    pub fn enforcement(&self, priority: &Priority, status: &Status, branch: Option<&str>) -> Enforcement {
+        let mut effective = self.clone();
+        if let Some(branch) = branch {
+            for b in &self.branches {
+                let matches = Glob::new(&b.pattern)
+                    .map(|g| g.compile_matcher().is_match(branch))
+                    .unwrap_or(false);
+                if matches {
+                    effective.must = b.must.unwrap_or(effective.must);
+                    effective.should = b.should.unwrap_or(effective.should);
+                    effective.prefer = b.prefer.unwrap_or(effective.prefer);
+                    effective.draft = b.draft.unwrap_or(effective.draft);
+                    effective.deprecated = b.deprecated.unwrap_or(effective.deprecated);
+                }
+            }
+        }
+
+        match (status, priority) {
+            (Status::Draft, _) => effective.draft,
+            (Status::Deprecated, _) => effective.deprecated,
+            (Status::Active, Priority::Must) => effective.must,
+            (Status::Active, Priority::Should) => effective.should,
+            (Status::Active, Priority::Prefer) => effective.prefer,
+        }
+    }
 }
 
 impl Config {
@@ -27,8 +235,14 @@ impl Config {
     pub fn load() -> Result<Self> {
         let path = "sigil.config.toml";
         match std::fs::read_to_string(path) {
-            Ok(content) => toml::from_str(&content)
-                .with_context(|| format!("Failed to parse {path}")),
+            Ok(content) => {
+                let mut config: Self = toml::from_str(&content)
+                    .with_context(|| format!("Failed to parse {path}"))?;
+                config.check().with_context(|| format!("Invalid {path}"))?;
+                config.compile_trigger_schemas().with_context(|| format!("Invalid {path}"))?;
+                config.schema.load_extension().with_context(|| format!("Invalid {path}"))?;
+                Ok(config)
+            }
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
                 Ok(Self::default())
             }
@@ -36,6 +250,32 @@ impl Config {
         }
     }
 
+    /// Semantic checks that deserialization alone cannot express.
+    fn check(&self) -> Result<()> {
+        if self.limits.max_file_bytes == 0 || self.limits.max_response_bytes == 0 {
+            anyhow::bail!("limits: max_file_bytes and max_response_bytes must be positive");
+        }
+        for pattern in &self.redaction.patterns {
+            regex::Regex::new(pattern)
+                .with_context(|| format!("redaction.patterns: invalid regex '{pattern}'"))?;
+        }
+        for b in &self.gate.branches {
+            Glob::new(&b.pattern)
+                .with_context(|| format!("gate.branches: invalid pattern '{}'", b.pattern))?;
+        }
+        Ok(())
+    }
+
+    /// Compile every schema of `trigger_schemas` into `trigger_validators`.
+    pub fn compile_trigger_schemas(&mut self) -> Result<()> {
+        for (kind, schema) in &self.trigger_schemas {
+            let validator = jsonschema::validator_for(schema)
+                .map_err(|e| anyhow::anyhow!("trigger_schemas.{kind}: invalid JSON Schema: {e}"))?;
+            self.trigger_validators.insert(kind.clone(), Arc::new(validator));
+        }
+        Ok(())
+    }
+
     /// Returns the instructions to deliver to agents: config override if set,
     /// otherwise the compile-time default.
     pub fn instructions(&self) -> &str {
@@ -51,6 +291,13 @@ impl Default for Config {
             contracts_dir: default_contracts_dir(),
             instructions: None,
             notes: None,
+            reviews_dir: default_reviews_dir(),
+            gate: GateConfig::default(),
+            limits: LimitsConfig::default(),
+            redaction: RedactionConfig::default(),
+            trigger_schemas: BTreeMap::new(),
+            trigger_validators: BTreeMap::new(),
+            schema: SchemaConfig::default(),
         }
     }
 }
@@ -59,6 +306,10 @@ fn default_contracts_dir() -> String {
     "contracts/".to_string()
 }
 
+fn default_reviews_dir() -> String {
+    ".sigil/reviews/".to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,9 +330,8 @@ mod tests {
     #[test]
     fn instructions_override_returned_when_set() {
         let config = Config {
-            contracts_dir: "contracts/".to_string(),
             instructions: Some("custom instructions".to_string()),
-            notes: None,
+            ..Config::default()
         };
         assert_eq!(config.instructions(), "custom instructions");
     }
@@ -107,6 +357,86 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn default_gate_follows_priority_and_status() {
+        let gate = GateConfig::default();
+        assert_eq!(gate.enforcement(&Priority::Must, &Status::Active, None), Enforcement::Block);
+        assert_eq!(gate.enforcement(&Priority::Should, &Status::Active, None), Enforcement::Warn);
+        assert_eq!(gate.enforcement(&Priority::Prefer, &Status::Active, None), Enforcement::Info);
+        assert_eq!(gate.enforcement(&Priority::Must, &Status::Draft, None), Enforcement::Ignore);
+        assert_eq!(gate.enforcement(&Priority::Must, &Status::Deprecated, None), Enforcement::Warn);
+    }
+
+    #[test]
+    fn branch_override_applies_only_to_matching_branches() {
+        let content = r#"
+[gate]
+should = "warn"
+
+[[gate.branches]]
+pattern = "release/*"
+should = "block"
+"#;
+        let config: Config = toml::from_str(content).unwrap();
+        let gate = &config.gate;
+        assert_eq!(gate.enforcement(&Priority::Should, &Status::Active, Some("release/1.2")), Enforcement::Block);
+        assert_eq!(gate.enforcement(&Priority::Should, &Status::Active, Some("feature/x")), Enforcement::Warn);
+        assert_eq!(gate.enforcement(&Priority::Should, &Status::Active, None), Enforcement::Warn);
+    }
+
+    #[test]
+    fn limits_default_and_override_per_field() {
+        let config: Config = toml::from_str("[limits]\nmax_file_bytes = 100\n").unwrap();
+        assert_eq!(config.limits.max_file_bytes, 100);
+        assert_eq!(config.limits.max_response_bytes, LimitsConfig::default().max_response_bytes);
+        assert!(config.check().is_ok());
+        let zero: Config = toml::from_str("[limits]\nmax_response_bytes = 0\n").unwrap();
+        assert!(zero.check().is_err());
+    }
+
+    #[test]
+    fn invalid_redaction_pattern_is_rejected() {
+        let config: Config = toml::from_str("[redaction]\npatterns = [\"(unclosed\"]\n").unwrap();
+        assert!(config.check().is_err());
+        assert!(config.redaction.enabled);
+    }
+
+    #[test]
+    fn trigger_schemas_are_keyed_by_type_and_must_compile() {
+        let content = r#"
+[trigger_schemas.kafka-message]
+type = "object"
+required = ["topic", "consumer_group"]
+"#;
+        let mut config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.trigger_schemas["kafka-message"]["required"][1], "consumer_group");
+        assert!(config.compile_trigger_schemas().is_ok());
+        assert!(config.trigger_validators.contains_key("kafka-message"));
+        let mut invalid: Config = toml::from_str("[trigger_schemas.http]\ntype = 42\n").unwrap();
+        assert!(invalid.compile_trigger_schemas().is_err());
+    }
+
+    #[test]
+    fn schema_extension_is_read_and_must_be_json_schema() {
+        let dir = std::env::temp_dir().join("sigil_config_test_schema_extension");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let valid = dir.join("extension.json");
+        std::fs::write(&valid, r#"{"properties": {"owner": {"type": "string"}}}"#).unwrap();
+        let invalid = dir.join("invalid.json");
+        std::fs::write(&invalid, r#"{"type": 42}"#).unwrap();
+
+        let mut config: Config =
+            toml::from_str(&format!("[schema]\nextension = {:?}\nstrict = true\n", valid.display().to_string())).unwrap();
+        config.schema.load_extension().unwrap();
+        assert!(config.schema.strict);
+        assert_eq!(config.schema.extension_schema.unwrap().schema["properties"]["owner"]["type"], "string");
+        for path in [invalid, dir.join("missing.json")] {
+            let mut schema = SchemaConfig { extension: Some(path.display().to_string()), ..SchemaConfig::default() };
+            assert!(schema.load_extension().is_err(), "{path:?}");
+        }
+    }
+
     #[test]
     fn missing_contracts_dir_field_defaults_to_contracts_slash() {
         let config: Config = toml::from_str("").unwrap();
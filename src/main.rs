@@ -1,13 +1,15 @@
+mod cli;
 mod config;
 pub mod model;
 mod tools;
 
 use anyhow::Result;
 use rmcp::ServiceExt;
+use std::process::ExitCode;
 use tools::SigilServer;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     // Log to stderr so stdout stays clean for MCP transport.
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -18,8 +20,14 @@ async fn main() -> Result<()> {
         .init();
 
     let cfg = config::Config::load()?;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        return cli::run(cfg, &args).await;
+    }
+
     let transport = (tokio::io::stdin(), tokio::io::stdout());
     let service = SigilServer::new(cfg).serve(transport).await?;
     service.waiting().await?;
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
@@ -2,7 +2,7 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum Priority {
     #[default]
@@ -11,7 +11,7 @@ pub enum Priority {
     Prefer,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum Status {
     #[default]
@@ -20,14 +20,14 @@ pub enum Status {
     Deprecated,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum AppliesTo {
     Single(String),
     Multiple(Vec<String>),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Trigger {
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub kind: Option<String>,
@@ -35,7 +35,7 @@ pub struct Trigger {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct Rule {
     pub id: String,
@@ -44,6 +44,60 @@ pub struct Rule {
     pub files: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub constraints: Option<Vec<String>>,
+    /// Deterministic assertions evaluated by validation, alongside the prose constraints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checks: Option<Vec<Check>>,
+    /// Tests confirming the rule holds: a file path, or `path::test_name`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tests: Option<Vec<String>>,
+}
+
+/// A machine-checkable assertion on a rule. Regex checks run over `paths`
+/// (globs over the project's files) or, when absent, the rule's files, falling
+/// back to the contract's files.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Check {
+    /// Every file in scope contains a match for `pattern`.
+    RegexMustMatch {
+        pattern: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        paths: Option<Vec<String>>,
+    },
+    /// No file in scope contains a match for `pattern`.
+    RegexMustNotMatch {
+        pattern: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        paths: Option<Vec<String>>,
+    },
+    /// `path` exists in the project.
+    FileExists { path: String },
+    /// The JSON document `file` has a node at `path`, equal to `value` when given.
+    JsonPathExists {
+        file: String,
+        path: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        value: Option<serde_json::Value>,
+    },
+    /// The TOML document `file` has a node at `path`, equal to `value` when given.
+    TomlPathExists {
+        file: String,
+        path: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        value: Option<serde_json::Value>,
+    },
+}
+
+impl Check {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Check::RegexMustMatch { .. } => "regex_must_match",
+            Check::RegexMustNotMatch { .. } => "regex_must_not_match",
+            Check::FileExists { .. } => "file_exists",
+            Check::JsonPathExists { .. } => "json_path_exists",
+            Check::TomlPathExists { .. } => "toml_path_exists",
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -55,6 +109,40 @@ pub struct ChangelogEntry {
     pub description: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Deprecation {
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub superseded_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<NaiveDate>,
+}
+
+/// A known, time-boxed violation of a contract (or one of its rules), read
+/// from the waivers file in the contracts directory.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Waiver {
+    pub contract_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    /// Glob patterns limiting the waiver to some files. Absent means the whole contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paths: Option<Vec<String>>,
+    pub reason: String,
+    pub owner: String,
+    /// Last day the waiver is valid.
+    pub expires: NaiveDate,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaiversFile {
+    #[serde(default)]
+    pub waivers: Vec<Waiver>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Contract {
     pub id: String,
@@ -80,9 +168,22 @@ pub struct Contract {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub overridable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecation: Option<Deprecation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub changelog: Option<Vec<ChangelogEntry>>,
     #[serde(flatten)]
     pub extra: serde_json::Map<String, serde_json::Value>,
+    /// File the contract was loaded from. Set by the loader, never serialized.
+    #[serde(skip)]
+    pub source: Option<String>,
+}
+
+impl Waiver {
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        today > self.expires
+    }
 }
 
 #[cfg(test)]
@@ -104,8 +205,11 @@ mod tests {
             files: None,
             rules: None,
             notes: None,
+            overridable: None,
+            deprecation: None,
             changelog: None,
             extra: serde_json::Map::new(),
+            source: None,
         }
     }
 
@@ -129,6 +233,8 @@ mod tests {
             description: "rule".to_string(),
             files: Some(vec!["schema/x.json".to_string()]),
             constraints: None,
+            checks: None,
+            tests: None,
         }]);
         assert_eq!(c.all_files(), vec!["schema/x.json"]);
     }
@@ -142,10 +248,25 @@ mod tests {
             description: "rule".to_string(),
             files: Some(vec!["schema/x.json".to_string()]),
             constraints: None,
+            checks: None,
+            tests: None,
         }]);
         assert_eq!(c.all_files(), vec!["src/main.rs", "schema/x.json"]);
     }
 
+    #[test]
+    fn checks_parse_by_kind() {
+        let rule: Rule = toml::from_str(
+            "id = \"r\"\ndescription = \"d\"\n[[checks]]\nkind = \"regex_must_not_match\"\npattern = \"println!\"\npaths = [\"src/**\"]\n[[checks]]\nkind = \"file_exists\"\npath = \"Cargo.toml\"\n",
+        )
+        .unwrap();
+        let checks = rule.checks.unwrap();
+        assert_eq!(checks[0].kind(), "regex_must_not_match");
+        assert_eq!(checks[1], Check::FileExists { path: "Cargo.toml".to_string() });
+        let unknown: Result<Check, _> = toml::from_str("kind = \"file_exists\"\npath = \"a\"\nextra = 1\n");
+        assert!(unknown.is_err());
+    }
+
     #[test]
     fn applies_to_patterns_none() {
         assert!(minimal().applies_to_patterns().is_empty());
@@ -190,6 +311,11 @@ impl Contract {
         paths
     }
 
+    /// Whether `sigil-override:` directives may waive this contract in CI. Defaults to true.
+    pub fn is_overridable(&self) -> bool {
+        self.overridable.unwrap_or(true)
+    }
+
     pub fn applies_to_patterns(&self) -> Vec<&str> {
         match &self.applies_to {
             None => vec![],
@@ -0,0 +1,43 @@
+use super::overrides;
+use rmcp::schemars;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {
+    /// Text containing `sigil-override: <contract-id> -- "reason"` lines (e.g. PR comments).
+    pub text: Option<String>,
+    /// Path to a file containing override text, relative to the project root. Used when `text` is not given.
+    pub path: Option<String>,
+}
+
+pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    let text = match (params.text, &params.path) {
+        (Some(text), _) => text,
+        (None, Some(path)) => match super::file_content::read_to_string(server.root(), path) {
+            Ok(text) => text,
+            Err(e) => return super::error_response(e),
+        },
+        (None, None) => return super::error_response("Provide either text or path"),
+    };
+
+    let (contracts, _) = super::loader::load_contracts(&server.config.contracts_dir);
+    let report = overrides::resolve(&text, &contracts);
+    serde_json::to_string(&report).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[tokio::test]
+    async fn reads_override_files_only_inside_the_project() {
+        let server = super::super::SigilServer::new(Config::default());
+        for path in ["/etc/passwd", "../outside.txt"] {
+            let result = handle(&server, Params { text: None, path: Some(path.into()) }).await;
+            assert!(result.contains("project root"), "{result}");
+        }
+        let result = handle(&server, Params { text: None, path: Some("Cargo.toml".into()) }).await;
+        assert!(!result.contains("error"), "{result}");
+    }
+}
@@ -0,0 +1,265 @@
+use super::file_content::Unresolved;
+use crate::model::{Check, Contract, Rule};
+use globset::Glob;
+use regex::Regex;
+use std::path::Path;
+
+/// Evaluate one check of `rule` against the files under the project `root`.
+/// Returns the failures (empty when the check holds), or an error when the
+/// check itself is unusable: an invalid regex or glob, or a path outside the
+/// project root. Failures name files and the check's own terms, never what
+/// the files contain.
+pub fn evaluate(
+    root: &Path,
+    check: &Check,
+    contract: &Contract,
+    rule: &Rule,
+    repo_files: &[String],
+) -> Result<Vec<String>, String> {
+    match check {
+        Check::RegexMustMatch { pattern, paths } => {
+            let re = regex(pattern)?;
+            let mut failures = Vec::new();
+            for file in scope(paths.as_deref(), contract, rule, repo_files)? {
+                match read(root, &file)? {
+                    Ok(text) if re.is_match(&text) => {}
+                    Ok(_) => failures.push(format!("'{file}' has no match for /{pattern}/")),
+                    Err(failure) => failures.push(failure),
+                }
+            }
+            Ok(failures)
+        }
+        Check::RegexMustNotMatch { pattern, paths } => {
+            let re = regex(pattern)?;
+            let mut failures = Vec::new();
+            for file in scope(paths.as_deref(), contract, rule, repo_files)? {
+                match read(root, &file)? {
+                    Ok(text) if re.is_match(&text) => failures.push(format!("'{file}' matches /{pattern}/")),
+                    Ok(_) => {}
+                    Err(failure) => failures.push(failure),
+                }
+            }
+            Ok(failures)
+        }
+        Check::FileExists { path } => match super::file_content::resolve(root, path) {
+            Ok(_) => Ok(vec![]),
+            Err(Unresolved::Rejected(reason)) => Err(reason),
+            Err(Unresolved::Missing) => Ok(vec![format!("'{path}' does not exist")]),
+            Err(Unresolved::Error(message)) => Ok(vec![format!("'{path}' cannot be read: {message}")]),
+        },
+        Check::JsonPathExists { file, path, value } => document(root, file, |text| serde_json::from_str(text).ok())
+            .map(|doc| doc.map_or_else(|e| vec![e], |doc| path_failures(file, &doc, path, value.as_ref()))),
+        Check::TomlPathExists { file, path, value } => document(root, file, |text| toml::from_str(text).ok())
+            .map(|doc| doc.map_or_else(|e| vec![e], |doc| path_failures(file, &doc, path, value.as_ref()))),
+    }
+}
+
+/// Read `file` from under `root`. The outer error is an unusable check (a
+/// path outside the project), the inner one a failure (missing or unreadable
+/// file).
+fn read(root: &Path, file: &str) -> Result<Result<String, String>, String> {
+    let resolved = match super::file_content::resolve(root, file) {
+        Ok(resolved) => resolved,
+        Err(Unresolved::Rejected(reason)) => return Err(reason),
+        Err(Unresolved::Missing) => return Ok(Err(format!("'{file}' does not exist"))),
+        Err(Unresolved::Error(message)) => return Ok(Err(format!("'{file}' cannot be read: {message}"))),
+    };
+    Ok(std::fs::read_to_string(resolved).map_err(|e| format!("'{file}' cannot be read: {e}")))
+}
+
+fn regex(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| format!("invalid regex '{pattern}': {e}"))
+}
+
+/// Files a regex check runs over. An empty scope is an error: a check that
+/// looks at nothing would pass forever.
+fn scope(paths: Option<&[String]>, contract: &Contract, rule: &Rule, repo_files: &[String]) -> Result<Vec<String>, String> {
+    let files: Vec<String> = match paths {
+        Some(patterns) => {
+            let mut matchers = Vec::new();
+            for pattern in patterns {
+                let glob = Glob::new(pattern).map_err(|e| format!("invalid path pattern '{pattern}': {e}"))?;
+                matchers.push(glob.compile_matcher());
+            }
+            repo_files
+                .iter()
+                .filter(|f| matchers.iter().any(|m| m.is_match(f.as_str())))
+                .cloned()
+                .collect()
+        }
+        None => {
+            let own = rule.files.as_deref().unwrap_or_default();
+            let listed = if own.is_empty() { contract.files.as_deref().unwrap_or_default() } else { own };
+            for file in listed {
+                super::file_content::check_reference(file)?;
+            }
+            listed.to_vec()
+        }
+    };
+    if files.is_empty() {
+        return Err("no files in scope: set 'paths' or list files on the rule".to_string());
+    }
+    Ok(files)
+}
+
+/// Parse `file` with `parse`. The outer error is an unusable check, the inner
+/// one a failure (missing or malformed document). Parse errors are reported
+/// without the parser's message, which may quote the document.
+fn document(
+    root: &Path,
+    file: &str,
+    parse: impl Fn(&str) -> Option<serde_json::Value>,
+) -> Result<Result<serde_json::Value, String>, String> {
+    Ok(read(root, file)?.and_then(|text| parse(&text).ok_or_else(|| format!("'{file}' cannot be parsed"))))
+}
+
+fn path_failures(file: &str, doc: &serde_json::Value, path: &str, value: Option<&serde_json::Value>) -> Vec<String> {
+    let nodes = select(doc, path);
+    match value {
+        None if nodes.is_empty() => vec![format!("'{file}' has no '{path}'")],
+        Some(expected) if !nodes.contains(&expected) => {
+            vec![format!("'{file}' has no '{path}' with the expected value")]
+        }
+        _ => vec![],
+    }
+}
+
+/// Nodes at a dotted `path`: each segment is an object key or array index,
+/// and `*` stands for every element of an array or value of an object.
+/// For example `fields.*.name` selects the name of every Avro field.
+fn select<'a>(doc: &'a serde_json::Value, path: &str) -> Vec<&'a serde_json::Value> {
+    let mut nodes = vec![doc];
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        nodes = nodes
+            .into_iter()
+            .flat_map(|node| -> Vec<&serde_json::Value> {
+                match (segment, node) {
+                    ("*", serde_json::Value::Array(items)) => items.iter().collect(),
+                    ("*", serde_json::Value::Object(map)) => map.values().collect(),
+                    (key, serde_json::Value::Object(map)) => map.get(key).into_iter().collect(),
+                    (index, serde_json::Value::Array(items)) => {
+                        index.parse::<usize>().ok().and_then(|i| items.get(i)).into_iter().collect()
+                    }
+                    _ => vec![],
+                }
+            })
+            .collect();
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(files: &[&str]) -> Contract {
+        toml::from_str(&format!(
+            "id = \"c\"\nversion = \"1.0.0\"\nname = \"c\"\ndescription = \"d\"\nfiles = {files:?}\n"
+        ))
+        .unwrap()
+    }
+
+    fn rule() -> Rule {
+        Rule {
+            id: "r".to_string(),
+            description: "d".to_string(),
+            files: None,
+            constraints: None,
+            checks: None,
+            tests: None,
+        }
+    }
+
+    const REPO_FILES: &[&str] = &["src/main.rs", "src/model.rs", "Cargo.toml", "schema/contract.schema.json"];
+
+    /// A small project in a temp dir holding `REPO_FILES`.
+    fn project(tag: &str) -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!("sigil_checks_test_{tag}"));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("schema")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("src/model.rs"), "pub struct Contract {\n    pub id: String,\n}\n").unwrap();
+        let manifest = "[package]\nname = \"demo\"\n\n\
+                        [dependencies]\nserde = { version = \"1\", features = [\"derive\"] }\n";
+        std::fs::write(root.join("Cargo.toml"), manifest).unwrap();
+        std::fs::write(root.join("schema/contract.schema.json"), r#"{"required": ["id", "version"]}"#).unwrap();
+        root
+    }
+
+    fn run(root: &Path, check: Check, files: &[&str]) -> Result<Vec<String>, String> {
+        let repo_files: Vec<String> = REPO_FILES.iter().map(|f| f.to_string()).collect();
+        evaluate(root, &check, &contract(files), &rule(), &repo_files)
+    }
+
+    #[test]
+    fn regex_checks_run_over_globs_or_contract_files() {
+        let root = project("regex");
+        let must = Check::RegexMustMatch { pattern: "pub struct Contract".to_string(), paths: None };
+        assert_eq!(run(&root, must, &["src/model.rs"]), Ok(vec![]));
+        let must_not = Check::RegexMustNotMatch {
+            pattern: "pub struct Contract".to_string(),
+            paths: Some(vec!["src/*.rs".to_string()]),
+        };
+        let failures = run(&root, must_not, &[]).unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures, ["'src/model.rs' matches /pub struct Contract/"]);
+    }
+
+    #[test]
+    fn unusable_checks_are_errors() {
+        let root = project("unusable");
+        let run = |check: Check, files: &[&str]| run(&root, check, files);
+        assert!(run(Check::RegexMustMatch { pattern: "(".to_string(), paths: None }, &["src/main.rs"]).is_err());
+        assert!(run(Check::RegexMustMatch { pattern: "x".to_string(), paths: None }, &[]).is_err());
+        assert!(run(Check::FileExists { path: "/etc/passwd".to_string() }, &[]).is_err());
+    }
+
+    #[test]
+    fn path_checks_select_nested_nodes() {
+        let root = project("paths");
+        let run = |check: Check, files: &[&str]| run(&root, check, files);
+        let toml_check = |path: &str, value: Option<serde_json::Value>| Check::TomlPathExists {
+            file: "Cargo.toml".to_string(),
+            path: path.to_string(),
+            value,
+        };
+        assert_eq!(run(toml_check("package.name", Some("demo".into())), &[]), Ok(vec![]));
+        assert_eq!(run(toml_check("dependencies.*.features", None), &[]), Ok(vec![]));
+        assert_eq!(run(toml_check("package.nope", None), &[]).unwrap().len(), 1);
+        let json_check = Check::JsonPathExists {
+            file: "schema/contract.schema.json".to_string(),
+            path: "required.0".to_string(),
+            value: Some("id".into()),
+        };
+        assert_eq!(run(json_check, &[]), Ok(vec![]));
+        let missing = Check::JsonPathExists { file: "nope.json".to_string(), path: "a".to_string(), value: None };
+        assert_eq!(run(missing, &[]), Ok(vec!["'nope.json' does not exist".to_string()]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn files_outside_the_project_are_never_read() {
+        let dir = std::env::temp_dir().join("sigil_checks_test_sandbox");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("project")).unwrap();
+        std::fs::create_dir_all(dir.join("outside")).unwrap();
+        std::fs::write(dir.join("outside/secret.json"), r#"{"token": "hunter2"}"#).unwrap();
+        std::fs::write(dir.join("project/bad.json"), r#"{"token": hunter2}"#).unwrap();
+        std::os::unix::fs::symlink(dir.join("outside/secret.json"), dir.join("project/link.json")).unwrap();
+        let root = dir.join("project");
+        let repo_files = vec!["link.json".to_string(), "bad.json".to_string()];
+        let eval = |check: Check| evaluate(&root, &check, &contract(&[]), &rule(), &repo_files);
+        let json = |file: &str| Check::JsonPathExists {
+            file: file.to_string(),
+            path: "token".to_string(),
+            value: Some("x".into()),
+        };
+
+        let regex = Check::RegexMustNotMatch { pattern: "hunter".to_string(), paths: Some(vec!["*.json".to_string()]) };
+        assert!(eval(regex).unwrap_err().contains("outside the project root"));
+        assert!(eval(json("link.json")).unwrap_err().contains("outside the project root"));
+        assert!(eval(Check::FileExists { path: "link.json".to_string() }).is_err());
+        assert_eq!(eval(json("bad.json")), Ok(vec!["'bad.json' cannot be parsed".to_string()]));
+    }
+}
@@ -1,13 +1,19 @@
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use toml;
 
-const SCHEMA_STR: &str = include_str!("../../schema/contract.schema.json");
-
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct Params {
     /// The full contract content as an object matching the contract schema.
-    pub contract: serde_json::Value,
+    /// Omit when creating from a template.
+    pub contract: Option<serde_json::Value>,
+    /// Name of a template in the contracts dir's templates/ subdirectory to
+    /// render instead of passing contract (see sigil_list_templates).
+    pub template: Option<String>,
+    /// Values for the template's {{variable}} placeholders. Every variable
+    /// of the template must be given.
+    pub variables: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Serialize)]
@@ -17,12 +23,24 @@ struct Response {
 }
 
 pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    let contract = match (params.contract, params.template) {
+        (Some(contract), None) => contract,
+        (None, Some(name)) => {
+            let rendered = super::templates::find_template(&server.config.contracts_dir, &name)
+                .and_then(|t| t.render(&params.variables.unwrap_or_default()));
+            match rendered {
+                Ok(contract) => contract,
+                Err(e) => return super::error_response(e),
+            }
+        }
+        (Some(_), Some(_)) => return super::error_response("Pass either contract or template, not both"),
+        (None, None) => return super::error_response("Pass the contract, or a template and its variables"),
+    };
+
     // Schema validation
-    let schema_json: serde_json::Value = serde_json::from_str(SCHEMA_STR).unwrap();
-    let validator = jsonschema::validator_for(&schema_json).expect("contract schema is valid JSON Schema");
-    let schema_errors: Vec<String> = validator
-        .iter_errors(&params.contract)
-        .map(|e| format!("{} at '{}'", e, e.instance_path))
+    let schema_errors: Vec<String> = super::schema::violations(&server.config, &contract)
+        .into_iter()
+        .map(|v| v.message)
         .collect();
     if !schema_errors.is_empty() {
         return serde_json::json!({ "error": "Schema validation failed", "validation": schema_errors })
@@ -30,28 +48,44 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
     }
 
     // Extract id
-    let Some(id) = params.contract.get("id").and_then(|v| v.as_str()) else {
+    let Some(id) = contract.get("id").and_then(|v| v.as_str()) else {
         return super::error_response("Contract must have an 'id' field");
     };
     let id = id.to_string();
 
     // Reject duplicate
-    let contracts_dir = server.config.contracts_dir.trim_end_matches('/');
-    let path = format!("{contracts_dir}/{id}.contract.toml");
-    if std::path::Path::new(&path).exists() {
+    let path = match super::loader::contract_path(&server.config.contracts_dir, &id) {
+        Ok(p) => p,
+        Err(e) => return super::error_response(e),
+    };
+    let (contracts, _) = super::loader::load_contracts(&server.config.contracts_dir);
+    let existing = contracts
+        .iter()
+        .find(|c| c.id == id)
+        .and_then(|c| c.source.clone())
+        .or_else(|| std::path::Path::new(&path).exists().then(|| path.clone()));
+    if let Some(existing) = existing {
         return super::error_response(format!(
-            "Contract '{id}' already exists at '{path}'. Use sigil_update_contract to modify it."
+            "Contract '{id}' already exists at '{existing}'. Use sigil_update_contract to modify it."
         ));
     }
 
     // Serialize to TOML via typed struct to get consistent field order
-    let toml_str = match serde_json::from_value::<crate::model::Contract>(params.contract.clone())
-        .map_err(|e| e.to_string())
-        .and_then(|c| toml::to_string_pretty(&c).map_err(|e| e.to_string()))
-    {
+    let contract = match serde_json::from_value::<crate::model::Contract>(contract) {
+        Ok(c) => c,
+        Err(e) => return super::error_response(format!("Failed to serialize contract: {e}")),
+    };
+    let unsafe_paths = super::file_content::unsafe_references(&contract);
+    if !unsafe_paths.is_empty() {
+        return serde_json::json!({ "error": "Unsafe file references", "validation": unsafe_paths })
+            .to_string();
+    }
+    let toml_str = match toml::to_string_pretty(&contract) {
         Ok(s) => s,
         Err(e) => return super::error_response(format!("Failed to serialize contract: {e}")),
     };
+    // Same layout as `sigil-mcp fmt`, so written contracts diff cleanly
+    let toml_str = super::format::format(&toml_str).unwrap_or(toml_str);
 
     // Write file
     if let Err(e) = std::fs::write(&path, &toml_str) {
@@ -60,11 +94,9 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
 
     // Warn on missing files
     let mut warnings = Vec::new();
-    if let Ok(contract) = serde_json::from_value::<crate::model::Contract>(params.contract) {
-        for path in contract.all_files() {
-            if !std::path::Path::new(path).exists() {
-                warnings.push(format!("File does not exist yet: '{path}'"));
-            }
+    for path in contract.all_files() {
+        if !std::path::Path::new(path).exists() {
+            warnings.push(format!("File does not exist yet: '{path}'"));
         }
     }
 
@@ -1,15 +1,44 @@
+use crate::model::{Contract, Deprecation, Priority, Status};
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
+use toml_edit::{DocumentMut, Item, Table, value};
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Remove the contract file.
+    #[default]
+    Delete,
+    /// Keep the file in place with status "deprecated" and a deprecation reason.
+    Deprecate,
+    /// Deprecate the contract and move it into the archive/ subdirectory,
+    /// where it is kept for history but no longer loaded.
+    Archive,
+}
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct Params {
     /// The id of the contract to delete.
     pub contract_id: String,
+    /// "delete" (default), "deprecate", or "archive".
+    pub mode: Option<Mode>,
+    /// Why the contract is being retired. Required for deprecate and archive,
+    /// rejected for delete, which keeps no record of it.
+    pub reason: Option<String>,
+    /// Id of an existing contract that replaces this one (deprecate and archive only).
+    pub superseded_by: Option<String>,
 }
 
 #[derive(Serialize)]
 struct Response {
-    deleted: String,
+    mode: Mode,
+    /// Path of the removed file (delete and archive).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted: Option<String>,
+    /// Path of the written file (deprecate and archive).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    warnings: Vec<String>,
 }
 
 pub async fn handle(server: &super::SigilServer, params: Params) -> String {
@@ -18,13 +47,329 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
     }
 
     let contracts_dir = server.config.contracts_dir.trim_end_matches('/');
-    let path = format!("{contracts_dir}/{}.contract.toml", params.contract_id);
+    let path = match super::loader::contract_path(contracts_dir, &params.contract_id) {
+        Ok(p) => p,
+        Err(e) => return super::error_response(e),
+    };
+    let mode = params.mode.unwrap_or_default();
+    if mode == Mode::Delete && (params.reason.is_some() || params.superseded_by.is_some()) {
+        return super::error_response(
+            "'reason' and 'superseded_by' are only recorded by modes 'deprecate' and 'archive'; \
+             a deleted contract keeps no history",
+        );
+    }
+
+    if !std::path::Path::new(&path).exists() {
+        return super::error_response(format!("Contract '{}' not found at '{path}'", params.contract_id));
+    }
+
+    let (contracts, _) = super::loader::load_contracts(&server.config.contracts_dir);
+    if let Err(e) = super::loader::find_unique(&contracts, &params.contract_id) {
+        return super::error_response(e);
+    }
+
+    if mode == Mode::Delete {
+        let warnings = hard_delete_warnings(&contracts, &params.contract_id);
+        return match std::fs::remove_file(&path) {
+            Ok(()) => serde_json::to_string(&Response {
+                mode,
+                deleted: Some(path),
+                path: None,
+                warnings,
+            })
+            .unwrap(),
+            Err(e) => super::error_response(format!("Failed to delete '{path}': {e}")),
+        };
+    }
+
+    let Some(reason) = params.reason.filter(|r| !r.trim().is_empty()) else {
+        return super::error_response("A non-empty 'reason' is required for deprecate and archive modes");
+    };
+    if let Some(successor) = &params.superseded_by {
+        if successor == &params.contract_id {
+            return super::error_response("A contract cannot supersede itself");
+        }
+        if !contracts.iter().any(|c| &c.id == successor) {
+            return super::error_response(format!(
+                "superseded_by refers to unknown contract '{successor}'"
+            ));
+        }
+    }
+
+    let deprecation = Deprecation {
+        reason,
+        superseded_by: params.superseded_by,
+        date: Some(chrono::Local::now().date_naive()),
+    };
+    let toml_str = match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| deprecate(&text, &deprecation))
+    {
+        Ok(s) => s,
+        Err(e) => return super::error_response(format!("Failed to deprecate '{path}': {e}")),
+    };
+
+    match mode {
+        Mode::Deprecate => match std::fs::write(&path, &toml_str) {
+            Ok(()) => serde_json::to_string(&Response {
+                mode,
+                deleted: None,
+                path: Some(path),
+                warnings: vec![],
+            })
+            .unwrap(),
+            Err(e) => super::error_response(format!("Failed to write '{path}': {e}")),
+        },
+        _ => {
+            let archive_dir = format!("{contracts_dir}/{}", super::loader::ARCHIVE_DIR);
+            let archive_path = format!("{archive_dir}/{}.contract.toml", params.contract_id);
+            // An earlier archived copy is history too; never replace it.
+            if std::path::Path::new(&archive_path).exists() {
+                return super::error_response(format!(
+                    "'{archive_path}' already holds an archived contract '{}'; nothing was changed. \
+                     Deprecate the contract instead, or move the archived copy aside first",
+                    params.contract_id
+                ));
+            }
+            if let Err(e) = std::fs::create_dir_all(&archive_dir)
+                .and_then(|()| std::fs::write(&archive_path, &toml_str))
+            {
+                return super::error_response(format!("Failed to write '{archive_path}': {e}"));
+            }
+            if let Err(e) = std::fs::remove_file(&path) {
+                return super::error_response(format!("Archived to '{archive_path}' but failed to remove '{path}': {e}"));
+            }
+            serde_json::to_string(&Response {
+                mode,
+                deleted: Some(path),
+                path: Some(archive_path),
+                warnings: vec![],
+            })
+            .unwrap()
+        }
+    }
+}
+
+/// The contract file `text` with status "deprecated" and `deprecation` set.
+/// The document is edited in place, so comments survive, and written in the
+/// same layout as `sigil-mcp fmt`.
+fn deprecate(text: &str, deprecation: &Deprecation) -> Result<String, String> {
+    toml::from_str::<Contract>(text).map_err(|e| e.message().to_string())?;
+    let mut doc: DocumentMut = text.parse().map_err(|e: toml_edit::TomlError| e.message().to_string())?;
+    doc["status"] = value("deprecated");
+    let mut table = Table::new();
+    table["reason"] = value(deprecation.reason.as_str());
+    if let Some(successor) = &deprecation.superseded_by {
+        table["superseded_by"] = value(successor.as_str());
+    }
+    if let Some(date) = deprecation.date {
+        table["date"] = value(date.to_string());
+    }
+    doc["deprecation"] = Item::Table(table);
+    let edited = doc.to_string();
+    Ok(super::format::format(&edited).unwrap_or(edited))
+}
+
+/// Facts a caller should know before a contract disappears without a trace.
+fn hard_delete_warnings(contracts: &[Contract], id: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
 
-    match std::fs::remove_file(&path) {
-        Ok(()) => serde_json::to_string(&Response { deleted: path }).unwrap(),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            super::error_response(format!("Contract '{}' not found at '{path}'", params.contract_id))
+    if let Some(c) = contracts.iter().find(|c| c.id == id)
+        && c.priority == Priority::Must
+        && c.status == Status::Active
+    {
+        warnings.push(format!(
+            "Contract '{id}' is an active 'must' contract. Consider mode 'deprecate' or 'archive' to keep its history."
+        ));
+    }
+
+    for other in contracts.iter().filter(|c| c.id != id) {
+        let superseded = other
+            .deprecation
+            .as_ref()
+            .and_then(|d| d.superseded_by.as_deref())
+            == Some(id);
+        if superseded {
+            warnings.push(format!("Contract '{}' is superseded_by '{id}'", other.id));
         }
-        Err(e) => super::error_response(format!("Failed to delete '{path}': {e}")),
+        if other.notes.as_deref().is_some_and(|n| mentions(n, id)) {
+            warnings.push(format!("Contract '{}' mentions '{id}' in its notes", other.id));
+        }
+    }
+
+    warnings
+}
+
+/// True if `text` contains `id` as a whole kebab-case token.
+fn mentions(text: &str, id: &str) -> bool {
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || c == '-';
+    text.match_indices(id).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + id.len()..].chars().next();
+        !before.is_some_and(is_id_char) && !after.is_some_and(is_id_char)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::fs;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_delete_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(dir: &std::path::Path, id: &str, extra: &str) {
+        let content = format!(
+            "id = \"{id}\"\nversion = \"1.0.0\"\nname = \"{id}\"\ndescription = \"desc\"\n{extra}"
+        );
+        fs::write(dir.join(format!("{id}.contract.toml")), content).unwrap();
+    }
+
+    fn make_server(dir: &std::path::Path, read: &str) -> super::super::SigilServer {
+        let server = super::super::SigilServer::new(Config {
+            contracts_dir: dir.to_str().unwrap().to_string(),
+            ..Config::default()
+        });
+        server.mark_read(read);
+        server
+    }
+
+    fn params(id: &str, mode: Mode, reason: Option<&str>, superseded_by: Option<&str>) -> Params {
+        Params {
+            contract_id: id.to_string(),
+            mode: Some(mode),
+            reason: reason.map(str::to_string),
+            superseded_by: superseded_by.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn hard_delete_warns_on_active_must_and_references() {
+        let dir = temp_dir("hard");
+        write(&dir, "old", "");
+        write(&dir, "other", "notes = \"See old for background.\"\n");
+        let both = "status = \"deprecated\"\nnotes = \"Use old.\"\n\n\
+                    [deprecation]\nreason = \"r\"\nsuperseded_by = \"old\"\n";
+        write(&dir, "both", both);
+        let server = make_server(&dir, "old");
+
+        let result = handle(&server, params("old", Mode::Delete, Some("obsolete"), None)).await;
+        assert!(result.contains("keeps no history"), "{result}");
+        assert!(dir.join("old.contract.toml").exists());
+
+        let result = handle(&server, params("old", Mode::Delete, None, None)).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json["deleted"].is_string());
+        assert!(!dir.join("old.contract.toml").exists());
+        let warnings: Vec<&str> = json["warnings"].as_array().unwrap().iter().map(|w| w.as_str().unwrap()).collect();
+        assert_eq!(
+            warnings,
+            [
+                "Contract 'old' is an active 'must' contract. Consider mode 'deprecate' or 'archive' to keep its history.",
+                "Contract 'both' is superseded_by 'old'",
+                "Contract 'both' mentions 'old' in its notes",
+                "Contract 'other' mentions 'old' in its notes",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn deprecate_keeps_file_and_records_reason() {
+        let dir = temp_dir("deprecate");
+        write(&dir, "old", "");
+        write(&dir, "new", "");
+        let server = make_server(&dir, "old");
+        let result = handle(&server, params("old", Mode::Deprecate, Some("replaced"), Some("new"))).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json.get("error").is_none(), "{result}");
+        let contract: Contract =
+            toml::from_str(&fs::read_to_string(dir.join("old.contract.toml")).unwrap()).unwrap();
+        assert_eq!(contract.status, Status::Deprecated);
+        let deprecation = contract.deprecation.unwrap();
+        assert_eq!(deprecation.reason, "replaced");
+        assert_eq!(deprecation.superseded_by.as_deref(), Some("new"));
+    }
+
+    #[tokio::test]
+    async fn deprecate_keeps_comments_in_the_canonical_layout() {
+        let dir = temp_dir("deprecate_comments");
+        let text = "# Owned by the payments team\nid = \"old\"\nname = \"old\"\nversion = \"1.0.0\"\n\
+                    description = \"desc\"\n\n[[rules]]\n# Keep this rule\nid = \"r\"\ndescription = \"d\"\n";
+        fs::write(dir.join("old.contract.toml"), text).unwrap();
+        let server = make_server(&dir, "old");
+        let result = handle(&server, params("old", Mode::Deprecate, Some("replaced"), None)).await;
+        assert!(!result.contains("error"), "{result}");
+        let written = fs::read_to_string(dir.join("old.contract.toml")).unwrap();
+        assert!(written.contains("# Owned by the payments team\n"), "{written}");
+        assert!(written.contains("# Keep this rule\n"), "{written}");
+        assert_eq!(super::super::format::format(&written).unwrap(), written, "Already in the canonical layout");
+        let contract: Contract = toml::from_str(&written).unwrap();
+        assert_eq!(contract.status, Status::Deprecated);
+        assert_eq!(contract.deprecation.unwrap().reason, "replaced");
+    }
+
+    #[tokio::test]
+    async fn archive_moves_file_out_of_loaded_set() {
+        let dir = temp_dir("archive");
+        write(&dir, "old", "");
+        let server = make_server(&dir, "old");
+        let result = handle(&server, params("old", Mode::Archive, Some("obsolete"), None)).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json.get("error").is_none(), "{result}");
+        assert!(!dir.join("old.contract.toml").exists());
+        assert!(dir.join("archive/old.contract.toml").exists());
+        let (contracts, _) = super::super::loader::load_contracts(dir.to_str().unwrap());
+        assert!(contracts.is_empty());
+
+        // Archiving the same id again keeps the first archived copy
+        let archived = fs::read_to_string(dir.join("archive/old.contract.toml")).unwrap();
+        write(&dir, "old", "");
+        let result = handle(&server, params("old", Mode::Archive, Some("again"), None)).await;
+        assert!(result.contains("already holds an archived contract"), "{result}");
+        assert!(dir.join("old.contract.toml").exists());
+        assert_eq!(fs::read_to_string(dir.join("archive/old.contract.toml")).unwrap(), archived);
+    }
+
+    #[tokio::test]
+    async fn soft_modes_require_reason() {
+        let dir = temp_dir("no_reason");
+        write(&dir, "old", "");
+        let server = make_server(&dir, "old");
+        let result = handle(&server, params("old", Mode::Deprecate, None, None)).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_successor() {
+        let dir = temp_dir("unknown_successor");
+        write(&dir, "old", "");
+        let server = make_server(&dir, "old");
+        let result = handle(&server, params("old", Mode::Deprecate, Some("r"), Some("missing"))).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json["error"].as_str().unwrap().contains("missing"));
+        assert!(fs::read_to_string(dir.join("old.contract.toml")).unwrap().contains("id = \"old\""));
+    }
+
+    #[tokio::test]
+    async fn rejects_ids_that_are_not_file_names() {
+        let dir = temp_dir("bad_id");
+        write(&dir, "old", "");
+        let server = make_server(&dir, "../old");
+        let result = handle(&server, params("../old", Mode::Delete, None, None)).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json["error"].as_str().unwrap().contains("../old"), "{result}");
+        assert!(dir.join("old.contract.toml").exists());
+    }
+
+    #[test]
+    fn mentions_matches_whole_ids_only() {
+        assert!(mentions("see no-pii for details", "no-pii"));
+        assert!(!mentions("see no-pii-in-logs", "no-pii"));
     }
 }
@@ -0,0 +1,354 @@
+use super::semantic_diff::{self, FieldChange, Scope};
+use crate::model::{Contract, Priority, Status};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {
+    /// Git ref to compare the contracts directory against (e.g. "origin/main").
+    pub base_ref: String,
+}
+
+#[derive(Serialize)]
+struct Response {
+    base_ref: String,
+    changes: Vec<Change>,
+    weakening: usize,
+    strengthening: usize,
+    neutral: usize,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Effect {
+    Weakening,
+    Strengthening,
+    Neutral,
+}
+
+#[derive(Serialize)]
+struct Change {
+    contract_id: String,
+    /// Priority of the contract before the change (or after, for new contracts).
+    priority: Priority,
+    effect: Effect,
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    detail: String,
+}
+
+pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    let contracts_dir = &server.config.contracts_dir;
+    let (current, mut warnings) = super::loader::load_contracts(contracts_dir);
+
+    let base = match load_at_ref(&params.base_ref, contracts_dir, &mut warnings) {
+        Ok(b) => b,
+        Err(e) => return super::error_response(e),
+    };
+
+    let mut changes = Vec::new();
+    for old in &base {
+        match current.iter().find(|c| c.id == old.id) {
+            Some(new) => compare(old, new, &mut changes),
+            None => changes.push(Change {
+                contract_id: old.id.clone(),
+                priority: old.priority.clone(),
+                effect: Effect::Weakening,
+                kind: "contract_removed",
+                rule_id: None,
+                detail: format!("Contract '{}' was removed", old.id),
+            }),
+        }
+    }
+    for new in current.iter().filter(|c| !base.iter().any(|b| b.id == c.id)) {
+        changes.push(Change {
+            contract_id: new.id.clone(),
+            priority: new.priority.clone(),
+            effect: Effect::Strengthening,
+            kind: "contract_added",
+            rule_id: None,
+            detail: format!("Contract '{}' was added", new.id),
+        });
+    }
+
+    let count = |effect| changes.iter().filter(|c| c.effect == effect).count();
+    let (weakening, strengthening, neutral) =
+        (count(Effect::Weakening), count(Effect::Strengthening), count(Effect::Neutral));
+
+    serde_json::to_string(&Response {
+        base_ref: params.base_ref,
+        changes,
+        weakening,
+        strengthening,
+        neutral,
+        warnings,
+    })
+    .unwrap()
+}
+
+fn load_at_ref(git_ref: &str, dir: &str, warnings: &mut Vec<String>) -> Result<Vec<Contract>, String> {
+    let mut contracts = Vec::new();
+    for path in super::git::list_files(git_ref, dir)? {
+        if !path.ends_with(".contract.toml")
+            || super::loader::is_archived(dir, std::path::Path::new(&path))
+        {
+            continue;
+        }
+        match super::git::show_file(git_ref, &path)? {
+            Some(content) => match toml::from_str::<Contract>(&content) {
+                Ok(c) => contracts.push(c),
+                Err(e) => warnings.push(format!("Failed to parse {path} at '{git_ref}': {e}")),
+            },
+            None => warnings.push(format!("Failed to read {path} at '{git_ref}'")),
+        }
+    }
+    Ok(contracts)
+}
+
+fn compare(old: &Contract, new: &Contract, changes: &mut Vec<Change>) {
+    let diff = semantic_diff::diff(old, new);
+    let mut push = |effect, kind, rule_id: Option<&str>, detail: String| {
+        changes.push(Change {
+            contract_id: old.id.clone(),
+            priority: old.priority.clone(),
+            effect,
+            kind,
+            rule_id: rule_id.map(str::to_string),
+            detail,
+        })
+    };
+
+    if let Some(t) = &diff.priority {
+        let effect = rank_effect(priority_rank(&t.from), priority_rank(&t.to));
+        push(effect, "priority_changed", None, format!("Priority changed from {} to {}", super::label(&t.from), super::label(&t.to)));
+    }
+
+    if let Some(t) = &diff.status {
+        let effect = rank_effect(status_rank(&t.from), status_rank(&t.to));
+        push(effect, "status_changed", None, format!("Status changed from {} to {}", super::label(&t.from), super::label(&t.to)));
+    }
+
+    if let Some(a) = &diff.applies_to {
+        for p in &a.removed {
+            push(Effect::Weakening, "applies_to_pattern_removed", None, format!("applies_to pattern '{p}' removed"));
+        }
+        for p in &a.added {
+            push(Effect::Strengthening, "applies_to_pattern_added", None, format!("applies_to pattern '{p}' added"));
+        }
+        if a.scope == Scope::Changed {
+            push(Effect::Neutral, "applies_to_changed", None, "applies_to patterns replaced".to_string());
+        }
+    }
+
+    for f in &diff.fields {
+        if f.field == "files" {
+            list_changes(f, ("file_removed", "file_added"), None, &mut push);
+        } else if f.field == "overridable" {
+            // Absent means overridable; allowing overrides weakens enforcement.
+            let allowed = |v: &serde_json::Value| v.as_bool().unwrap_or(true);
+            let effect = rank_effect(!allowed(&f.old) as u8, !allowed(&f.new) as u8);
+            push(effect, "overridable_changed", None, format!("overridable changed from {} to {}", allowed(&f.old), allowed(&f.new)));
+        } else {
+            push(Effect::Neutral, "field_changed", None, format!("Field '{}' changed", f.field));
+        }
+    }
+
+    for id in &diff.rules.removed {
+        push(Effect::Weakening, "rule_removed", Some(id), format!("Rule '{id}' removed"));
+    }
+    for id in &diff.rules.added {
+        push(Effect::Strengthening, "rule_added", Some(id), format!("Rule '{id}' added"));
+    }
+    for r in &diff.rules.renamed {
+        push(Effect::Neutral, "rule_renamed", Some(&r.to), format!("Rule '{}' renamed to '{}'", r.from, r.to));
+    }
+    for rc in &diff.rules.changed {
+        for c in &rc.constraints_removed {
+            push(Effect::Weakening, "constraint_removed", Some(&rc.id), format!("Constraint removed: {c}"));
+        }
+        for c in &rc.constraints_added {
+            push(Effect::Strengthening, "constraint_added", Some(&rc.id), format!("Constraint added: {c}"));
+        }
+        for f in &rc.fields {
+            if f.field == "files" {
+                list_changes(f, ("rule_file_removed", "rule_file_added"), Some(&rc.id), &mut push);
+            } else if f.field == "checks" {
+                check_changes(f, &rc.id, &mut push);
+            } else {
+                push(Effect::Neutral, "rule_field_changed", Some(&rc.id), format!("Rule field '{}' changed", f.field));
+            }
+        }
+    }
+}
+
+/// Report items removed from / added to a string list field (e.g. `files`) individually.
+fn list_changes(
+    change: &FieldChange,
+    (removed_kind, added_kind): (&'static str, &'static str),
+    rule_id: Option<&str>,
+    push: &mut impl FnMut(Effect, &'static str, Option<&str>, String),
+) {
+    let as_list = |v: &serde_json::Value| -> Vec<String> {
+        v.as_array()
+            .map(|a| a.iter().filter_map(|s| s.as_str().map(str::to_string)).collect())
+            .unwrap_or_default()
+    };
+    let (old, new) = (as_list(&change.old), as_list(&change.new));
+    for f in old.iter().filter(|f| !new.contains(f)) {
+        push(Effect::Weakening, removed_kind, rule_id, format!("File reference '{f}' removed"));
+    }
+    for f in new.iter().filter(|f| !old.contains(f)) {
+        push(Effect::Strengthening, added_kind, rule_id, format!("File reference '{f}' added"));
+    }
+}
+
+/// A removed or edited check no longer asserts what it did; an edit counts as both.
+fn check_changes(
+    change: &FieldChange,
+    rule_id: &str,
+    push: &mut impl FnMut(Effect, &'static str, Option<&str>, String),
+) {
+    let as_list = |v: &serde_json::Value| v.as_array().cloned().unwrap_or_default();
+    let (old, new) = (as_list(&change.old), as_list(&change.new));
+    let describe = |c: &serde_json::Value| {
+        let kind = c["kind"].as_str().unwrap_or("check");
+        let target = ["pattern", "path", "file"].iter().find_map(|k| c[*k].as_str()).unwrap_or_default();
+        format!("{kind} '{target}'")
+    };
+    for c in old.iter().filter(|c| !new.contains(c)) {
+        push(Effect::Weakening, "check_removed", Some(rule_id), format!("Check removed: {}", describe(c)));
+    }
+    for c in new.iter().filter(|c| !old.contains(c)) {
+        push(Effect::Strengthening, "check_added", Some(rule_id), format!("Check added: {}", describe(c)));
+    }
+}
+
+fn rank_effect(from: u8, to: u8) -> Effect {
+    match to.cmp(&from) {
+        std::cmp::Ordering::Less => Effect::Weakening,
+        std::cmp::Ordering::Greater => Effect::Strengthening,
+        std::cmp::Ordering::Equal => Effect::Neutral,
+    }
+}
+
+fn priority_rank(p: &Priority) -> u8 {
+    match p {
+        Priority::Must => 2,
+        Priority::Should => 1,
+        Priority::Prefer => 0,
+    }
+}
+
+/// Active contracts are fully enforced, deprecated ones only warn, drafts are not enforced.
+fn status_rank(s: &Status) -> u8 {
+    match s {
+        Status::Active => 2,
+        Status::Deprecated => 1,
+        Status::Draft => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml_str: &str) -> Contract {
+        toml::from_str(toml_str).unwrap()
+    }
+
+    const BASE: &str = r#"
+id = "c"
+version = "1.0.0"
+name = "C"
+description = "desc"
+applies_to = ["src/**", "tests/**"]
+files = ["a.rs"]
+
+[[rules]]
+id = "r1"
+description = "first"
+constraints = ["x", "y"]
+"#;
+
+    fn kinds(changes: &[Change], effect: Effect) -> Vec<&'static str> {
+        changes.iter().filter(|c| c.effect == effect).map(|c| c.kind).collect()
+    }
+
+    #[test]
+    fn identical_contracts_produce_no_changes() {
+        let mut changes = Vec::new();
+        compare(&parse(BASE), &parse(BASE), &mut changes);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn classifies_weakening_changes() {
+        let old = parse(BASE);
+        let mut new = old.clone();
+        new.priority = Priority::Prefer;
+        new.status = Status::Deprecated;
+        new.applies_to = Some(crate::model::AppliesTo::Single("src/**".into()));
+        new.files = None;
+        new.rules.as_mut().unwrap()[0].constraints = Some(vec!["x".into()]);
+
+        let mut changes = Vec::new();
+        compare(&old, &new, &mut changes);
+
+        let weakening = kinds(&changes, Effect::Weakening);
+        for kind in [
+            "priority_changed",
+            "status_changed",
+            "applies_to_pattern_removed",
+            "file_removed",
+            "constraint_removed",
+        ] {
+            assert!(weakening.contains(&kind), "expected {kind} to be weakening: {weakening:?}");
+        }
+        assert!(kinds(&changes, Effect::Strengthening).is_empty());
+    }
+
+    #[test]
+    fn classifies_strengthening_changes() {
+        let mut old = parse(BASE);
+        old.priority = Priority::Should;
+        let mut new = old.clone();
+        new.priority = Priority::Must;
+        new.rules.as_mut().unwrap()[0].constraints = Some(vec!["x".into(), "y".into(), "z".into()]);
+
+        let mut changes = Vec::new();
+        compare(&old, &new, &mut changes);
+
+        assert_eq!(kinds(&changes, Effect::Strengthening), vec!["priority_changed", "constraint_added"]);
+        assert!(kinds(&changes, Effect::Weakening).is_empty());
+    }
+
+    #[test]
+    fn removing_a_check_is_weakening() {
+        let mut old = parse(BASE);
+        old.rules.as_mut().unwrap()[0].checks = Some(vec![crate::model::Check::FileExists { path: "a.rs".into() }]);
+        let mut new = old.clone();
+        new.rules.as_mut().unwrap()[0].checks = None;
+
+        let mut changes = Vec::new();
+        compare(&old, &new, &mut changes);
+        assert_eq!(kinds(&changes, Effect::Weakening), vec!["check_removed"]);
+
+        let mut changes = Vec::new();
+        compare(&new, &old, &mut changes);
+        assert_eq!(kinds(&changes, Effect::Strengthening), vec!["check_added"]);
+    }
+
+    #[test]
+    fn description_edits_are_neutral() {
+        let old = parse(BASE);
+        let mut new = old.clone();
+        new.description = "reworded".into();
+
+        let mut changes = Vec::new();
+        compare(&old, &new, &mut changes);
+
+        assert_eq!(kinds(&changes, Effect::Neutral), vec!["field_changed"]);
+    }
+}
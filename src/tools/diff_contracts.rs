@@ -0,0 +1,148 @@
+use super::semantic_diff::{self, ContractDiff};
+use crate::model::Contract;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {
+    /// Path to the contract file to compare (the new side), relative to the project root.
+    pub path: String,
+    /// Path to another contract file to use as the old side, relative to the project root.
+    pub base_path: Option<String>,
+    /// Git ref (e.g. "main", "HEAD~1") whose version of `path` is used as the old side.
+    pub base_ref: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    base: String,
+    path: String,
+    identical: bool,
+    diff: ContractDiff,
+}
+
+pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    let new_toml = match super::file_content::read_to_string(server.root(), &params.path) {
+        Ok(s) => s,
+        Err(e) => return super::error_response(e),
+    };
+    let (base, old_toml) = match (&params.base_path, &params.base_ref) {
+        (Some(base_path), None) => match super::file_content::read_to_string(server.root(), base_path) {
+            Ok(s) => (base_path.clone(), s),
+            Err(e) => return super::error_response(e),
+        },
+        (None, Some(git_ref)) => match super::git::show_file(git_ref, &params.path) {
+            Ok(Some(s)) => (format!("{git_ref}:{}", params.path), s),
+            Ok(None) => {
+                return super::error_response(format!(
+                    "'{}' does not exist at git ref '{git_ref}'",
+                    params.path
+                ));
+            }
+            Err(e) => return super::error_response(e),
+        },
+        _ => return super::error_response("Provide exactly one of base_path or base_ref"),
+    };
+
+    let old = match parse(&base, &old_toml) {
+        Ok(c) => c,
+        Err(e) => return super::error_response(e),
+    };
+    let new = match parse(&params.path, &new_toml) {
+        Ok(c) => c,
+        Err(e) => return super::error_response(e),
+    };
+
+    let diff = semantic_diff::diff(&old, &new);
+    serde_json::to_string(&Response {
+        base,
+        path: params.path,
+        identical: diff.is_empty(),
+        diff,
+    })
+    .unwrap()
+}
+
+/// Parse a contract. The error names the line but, unlike the parser's own
+/// message, does not quote it: the file may not be a contract at all.
+fn parse(name: &str, text: &str) -> Result<Contract, String> {
+    toml::from_str(text).map_err(|e: toml::de::Error| {
+        let line = e.span().map(|range| super::locate::Span::from_range(text, range).line);
+        match line {
+            Some(line) => format!("Failed to parse '{name}' at line {line}: {}", e.message()),
+            None => format!("Failed to parse '{name}': {}", e.message()),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::fs;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_diff_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(dir: &std::path::Path, name: &str, priority: &str) -> String {
+        fs::write(
+            dir.join(name),
+            format!("id = \"c\"\nversion = \"1.0.0\"\nname = \"C\"\ndescription = \"d\"\npriority = \"{priority}\"\n"),
+        )
+        .unwrap();
+        name.to_string()
+    }
+
+    fn server(root: &std::path::Path) -> super::super::SigilServer {
+        let mut server = super::super::SigilServer::new(Config::default());
+        server.root = root.to_path_buf();
+        server
+    }
+
+    #[tokio::test]
+    async fn compares_two_files() {
+        let dir = temp_dir("two_files");
+        let old = write(&dir, "old.contract.toml", "must");
+        let new = write(&dir, "new.contract.toml", "prefer");
+        let server = server(&dir);
+        let result = handle(&server, Params { path: new, base_path: Some(old), base_ref: None }).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["identical"], false);
+        assert_eq!(json["diff"]["priority"]["from"], "must");
+        assert_eq!(json["diff"]["priority"]["to"], "prefer");
+    }
+
+    #[tokio::test]
+    async fn requires_exactly_one_base() {
+        let server = super::super::SigilServer::new(Config::default());
+        let result = handle(
+            &server,
+            Params { path: "x".into(), base_path: None, base_ref: None },
+        )
+        .await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn reads_only_inside_the_project_and_never_echoes_contents() {
+        let dir = temp_dir("sandbox");
+        let new = write(&dir, "new.contract.toml", "must");
+        fs::write(dir.join("secret.toml"), "password = hunter2\n").unwrap();
+        let server = server(&dir);
+        for base in ["/etc/passwd", "../outside.toml"] {
+            let params = Params { path: new.clone(), base_path: Some(base.into()), base_ref: None };
+            let result = handle(&server, params).await;
+            assert!(result.contains("error") && result.contains("project root"), "{result}");
+        }
+
+        let params = Params { path: new, base_path: Some("secret.toml".into()), base_ref: None };
+        let result = handle(&server, params).await;
+        assert!(result.contains("line 1"), "{result}");
+        assert!(!result.contains("hunter2"), "Parse errors must not quote the file: {result}");
+    }
+}
@@ -0,0 +1,36 @@
+use rmcp::schemars;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {
+    /// Review session whose recorded verdicts should be included. Omit to gate on validation only.
+    pub review_id: Option<String>,
+    /// Branch being gated, for branch-specific overrides in sigil.config.toml.
+    pub branch: Option<String>,
+    /// Text containing `sigil-override: <contract-id> -- "reason"` lines (e.g. PR comments).
+    pub overrides: Option<String>,
+    /// Path to a file containing override text, relative to the project root. Used when `overrides` is not given.
+    pub overrides_path: Option<String>,
+}
+
+pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    let override_text = match (params.overrides, &params.overrides_path) {
+        (Some(text), _) => Some(text),
+        (None, Some(path)) => match super::file_content::read_to_string(server.root(), path) {
+            Ok(text) => Some(text),
+            Err(e) => return super::error_response(e),
+        },
+        (None, None) => None,
+    };
+
+    match super::gate::evaluate(
+        &server.config,
+        server.root(),
+        params.review_id.as_deref(),
+        params.branch.as_deref(),
+        override_text.as_deref(),
+    ) {
+        Ok(report) => serde_json::to_string(&report).unwrap(),
+        Err(e) => super::error_response(e),
+    }
+}
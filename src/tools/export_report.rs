@@ -0,0 +1,60 @@
+use super::reviews;
+use super::validation;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    /// SARIF 2.1.0 log, for code-scanning UIs.
+    Sarif,
+    /// JUnit XML, one testcase per contract, for CI test report views.
+    Junit,
+    /// Markdown summary grouped by priority, for PR descriptions and comments.
+    Markdown,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {
+    /// Report format.
+    pub format: Format,
+    /// Review session whose recorded verdicts should be included. Omit to report validation only.
+    pub review_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    format: Format,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    review_id: Option<String>,
+    /// The rendered report.
+    content: String,
+}
+
+pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    let session = match &params.review_id {
+        Some(id) => match reviews::load(&server.config.reviews_dir, id) {
+            Ok(s) => Some(s),
+            Err(e) => return super::error_response(e),
+        },
+        None => None,
+    };
+    let validation = validation::validate_all(&server.config, server.root());
+    let (contracts, _) = super::loader::load_contracts(&server.config.contracts_dir);
+
+    let content = match params.format {
+        Format::Sarif => {
+            let log = super::sarif::render(&contracts, &validation, session.as_ref());
+            serde_json::to_string_pretty(&log).unwrap()
+        }
+        Format::Junit => super::junit::render(&contracts, &validation, session.as_ref()),
+        Format::Markdown => super::markdown::render(&contracts, &validation, session.as_ref()),
+    };
+
+    serde_json::to_string(&Response {
+        format: params.format,
+        review_id: params.review_id,
+        content,
+    })
+    .unwrap()
+}
@@ -0,0 +1,433 @@
+use super::redaction::Redactor;
+use crate::config::Config;
+use crate::model::Contract;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+
+/// A file referenced by a contract, as returned to agents.
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum FileContent {
+    /// `redactions` counts the secrets replaced in `contents`.
+    Ok {
+        contents: String,
+        #[serde(skip_serializing_if = "is_zero")]
+        redactions: usize,
+    },
+    /// Larger than the allowed size: only the start and the end are included,
+    /// with `omitted_bytes` missing in between. Sizes are of the redacted text,
+    /// except for files too large to read whole, where `size` is the size on
+    /// disk and only the excerpts are read and redacted.
+    Truncated {
+        size: usize,
+        head: String,
+        tail: String,
+        omitted_bytes: usize,
+        #[serde(skip_serializing_if = "is_zero")]
+        redactions: usize,
+    },
+    /// Not valid UTF-8 text; identified by size and SHA-256 instead of contents.
+    Binary { size: usize, sha256: String },
+    /// Not read because the response byte limit was already used up.
+    Omitted { size: usize },
+    Missing,
+    /// The reference points outside the project root and was not read.
+    Rejected { reason: String },
+    Error { message: String },
+}
+
+impl FileContent {
+    /// A warning for the caller when the file was not returned in full.
+    pub fn warning(&self, path: &str) -> Option<String> {
+        match self {
+            FileContent::Ok { redactions: 0, .. } | FileContent::Binary { .. } => None,
+            FileContent::Ok { redactions, .. } => {
+                Some(format!("Redacted {redactions} secret(s) in '{path}'"))
+            }
+            FileContent::Truncated { size, omitted_bytes, redactions, .. } => {
+                let mut warning = format!("Truncated file '{path}': {omitted_bytes} of {size} bytes omitted");
+                if *redactions > 0 {
+                    warning.push_str(&format!("; redacted {redactions} secret(s)"));
+                }
+                Some(warning)
+            }
+            FileContent::Omitted { size } => {
+                Some(format!("Omitted file '{path}' ({size} bytes): response byte limit reached"))
+            }
+            FileContent::Missing => Some(format!("Missing file: '{path}'")),
+            FileContent::Rejected { reason } => Some(format!("Refused to read file: {reason}")),
+            FileContent::Error { message } => Some(format!("Error reading file '{path}': {message}")),
+        }
+    }
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// Check a file reference without touching the filesystem: it must be
+/// relative to the project root and must not climb above it with `..`.
+pub fn check_reference(path: &str) -> Result<(), String> {
+    let mut depth = 0usize;
+    for component in Path::new(path).components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => {
+                return Err(format!("'{path}' is an absolute path; file references must be relative to the project root"));
+            }
+            Component::ParentDir if depth == 0 => {
+                return Err(format!("'{path}' escapes the project root"));
+            }
+            Component::ParentDir => depth -= 1,
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+        }
+    }
+    Ok(())
+}
+
+/// Problems with every file reference of `contract` that fails [`check_reference`].
+pub fn unsafe_references(contract: &Contract) -> Vec<String> {
+    contract
+        .all_files()
+        .into_iter()
+        .filter_map(|path| check_reference(path).err())
+        .collect()
+}
+
+/// The project root: file references are relative to the current directory.
+pub const PROJECT_ROOT: &str = ".";
+
+/// Why a file reference could not be resolved to a file in the project.
+pub enum Unresolved {
+    /// Outside the project root, lexically or through a symlink.
+    Rejected(String),
+    Missing,
+    Error(String),
+}
+
+/// The canonical path of the file `path` refers to, relative to the project
+/// `root`. Refuses references that are absolute, climb above the root with
+/// `..`, or resolve outside it through symlinks.
+pub fn resolve(root: &Path, path: &str) -> Result<PathBuf, Unresolved> {
+    check_reference(path).map_err(Unresolved::Rejected)?;
+    let root = std::fs::canonicalize(root)
+        .map_err(|e| Unresolved::Error(format!("Cannot resolve project root: {e}")))?;
+    let resolved = match std::fs::canonicalize(root.join(path)) {
+        Ok(p) => p,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(Unresolved::Missing),
+        Err(e) => return Err(Unresolved::Error(e.to_string())),
+    };
+    if !resolved.starts_with(&root) {
+        return Err(Unresolved::Rejected(format!(
+            "'{path}' resolves to '{}', outside the project root",
+            resolved.display()
+        )));
+    }
+    Ok(resolved)
+}
+
+/// Read a text file referenced relative to `root`, refusing anything
+/// outside it like [`Reader::read`] does. Error messages never include the
+/// file's contents.
+pub fn read_to_string(root: &Path, path: &str) -> Result<String, String> {
+    let resolved = match resolve(root, path) {
+        Ok(p) => p,
+        Err(Unresolved::Rejected(reason)) => return Err(reason),
+        Err(Unresolved::Missing) => return Err(format!("'{path}' does not exist")),
+        Err(Unresolved::Error(message)) => return Err(format!("Failed to read '{path}': {message}")),
+    };
+    std::fs::read_to_string(resolved).map_err(|e| format!("Failed to read '{path}': {e}"))
+}
+
+/// Reads referenced files for one response, redacting secrets and applying
+/// the per-file and per-response byte limits.
+pub struct Reader {
+    root: PathBuf,
+    max_file_bytes: usize,
+    remaining: usize,
+    redactor: Redactor,
+}
+
+impl Reader {
+    /// A reader for files under the project `root`, normally the current directory.
+    pub fn new(config: &Config, root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            max_file_bytes: config.limits.max_file_bytes,
+            remaining: config.limits.max_response_bytes,
+            redactor: Redactor::new(&config.redaction),
+        }
+    }
+
+    /// Read a referenced file, refusing anything outside the project root,
+    /// including files reached through symlinks.
+    pub fn read(&mut self, path: &str) -> FileContent {
+        let resolved = match resolve(&self.root, path) {
+            Ok(p) => p,
+            Err(Unresolved::Rejected(reason)) => return FileContent::Rejected { reason },
+            Err(Unresolved::Missing) => return FileContent::Missing,
+            Err(Unresolved::Error(message)) => return FileContent::Error { message },
+        };
+        self.read_file(path, &resolved).unwrap_or_else(|e| FileContent::Error { message: e.to_string() })
+    }
+
+    /// Read the file at `resolved` whole if it is small enough, otherwise only
+    /// what the response needs: a streamed hash for a binary file, nothing for
+    /// an omitted one, and the head and tail for a truncated one.
+    fn read_file(&mut self, path: &str, resolved: &Path) -> std::io::Result<FileContent> {
+        let mut file = File::open(resolved)?;
+        let size = usize::try_from(file.metadata()?.len()).unwrap_or(usize::MAX);
+        let allowance = self.max_file_bytes.min(self.remaining);
+        if size <= allowance.saturating_add(2 * REDACTION_MARGIN) {
+            let mut bytes = Vec::with_capacity(size);
+            file.read_to_end(&mut bytes)?;
+            return Ok(self.excerpt(path, bytes));
+        }
+
+        if read_range(&mut file, 0, BINARY_SNIFF_BYTES)?.contains(&0) {
+            return binary_file(&mut file, size);
+        }
+        if self.remaining == 0 {
+            return Ok(FileContent::Omitted { size });
+        }
+        // Each excerpt is redacted together with the margin beyond its cut, so
+        // a secret crossing the cut is matched whole before it is cut off.
+        let half = allowance / 2;
+        let head = read_range(&mut file, 0, allowance - half + REDACTION_MARGIN)?;
+        let tail = read_range(&mut file, (size - half - REDACTION_MARGIN) as u64, half + REDACTION_MARGIN)?;
+        let (Some(head), Some(tail)) = (window_text(&head), window_text(&tail)) else {
+            return binary_file(&mut file, size);
+        };
+        let (head, head_redactions) = self.redactor.redact(path, head);
+        let (tail, tail_redactions) = self.redactor.redact(path, tail);
+        let head_end = head.floor_char_boundary(allowance - half);
+        let tail_start = tail.ceil_char_boundary(tail.len().saturating_sub(half));
+        let (head, tail) = (head[..head_end].to_string(), tail[tail_start..].to_string());
+        self.remaining -= head.len() + tail.len();
+        Ok(FileContent::Truncated {
+            size,
+            omitted_bytes: size.saturating_sub(head.len() + tail.len()),
+            head,
+            tail,
+            redactions: head_redactions + tail_redactions,
+        })
+    }
+
+    fn excerpt(&mut self, path: &str, bytes: Vec<u8>) -> FileContent {
+        let size = bytes.len();
+        if bytes[..size.min(BINARY_SNIFF_BYTES)].contains(&0) {
+            return binary(&bytes);
+        }
+        let (text, redactions) = match String::from_utf8(bytes) {
+            Ok(text) => self.redactor.redact(path, text),
+            Err(e) => return binary(e.as_bytes()),
+        };
+        let size = text.len();
+        if self.remaining == 0 {
+            return FileContent::Omitted { size };
+        }
+        let allowance = self.max_file_bytes.min(self.remaining);
+        if size <= allowance {
+            self.remaining -= size;
+            return FileContent::Ok { contents: text, redactions };
+        }
+        let head_end = text.floor_char_boundary(allowance - allowance / 2);
+        let tail_start = text.ceil_char_boundary(size - allowance / 2);
+        self.remaining -= head_end + (size - tail_start);
+        FileContent::Truncated {
+            size,
+            head: text[..head_end].to_string(),
+            tail: text[tail_start..].to_string(),
+            omitted_bytes: tail_start - head_end,
+            redactions,
+        }
+    }
+}
+
+/// Files with a NUL byte this early are treated as binary even if they are valid UTF-8.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// Bytes read beyond each cut of a file too large to read whole, so that
+/// secrets crossing the cut (up to a large PEM key) are redacted whole.
+/// Files within twice this of the per-file limit are read whole.
+const REDACTION_MARGIN: usize = 16 * 1024;
+
+fn binary(bytes: &[u8]) -> FileContent {
+    FileContent::Binary { size: bytes.len(), sha256: hex(&Sha256::digest(bytes)) }
+}
+
+/// A binary file identified by a hash streamed over its contents.
+fn binary_file(file: &mut File, size: usize) -> std::io::Result<FileContent> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(file, &mut hasher)?;
+    Ok(FileContent::Binary { size, sha256: hex(&hasher.finalize()) })
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Up to `len` bytes of `file` from byte `from`.
+fn read_range(file: &mut File, from: u64, len: usize) -> std::io::Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(from))?;
+    let mut bytes = Vec::with_capacity(len);
+    file.take(len as u64).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A window of a file as text, dropping a character cut in two at either
+/// end. None if the window is not UTF-8.
+fn window_text(bytes: &[u8]) -> Option<String> {
+    let start = bytes.iter().take(3).take_while(|b| *b & 0xc0 == 0x80).count();
+    let bytes = &bytes[start..];
+    let end = match std::str::from_utf8(bytes) {
+        Ok(_) => bytes.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => return None,
+    };
+    String::from_utf8(bytes[..end].to_vec()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_absolute_and_escaping_references() {
+        assert!(check_reference("/etc/passwd").is_err());
+        assert!(check_reference("../../etc/passwd").is_err());
+        assert!(check_reference("src/../../outside.rs").is_err());
+        assert!(check_reference("src/../README.md").is_ok());
+        assert!(check_reference("./src/main.rs").is_ok());
+    }
+
+    use crate::config::LimitsConfig;
+
+    fn read(path: &str) -> FileContent {
+        Reader::new(&Config::default(), Path::new(PROJECT_ROOT)).read(path)
+    }
+
+    fn reader(max_file_bytes: usize, max_response_bytes: usize) -> Reader {
+        Reader::new(
+            &Config {
+                limits: LimitsConfig { max_file_bytes, max_response_bytes },
+                ..Config::default()
+            },
+            Path::new(PROJECT_ROOT),
+        )
+    }
+
+    #[test]
+    fn reads_project_files_and_refuses_others() {
+        assert!(matches!(read("src/main.rs"), FileContent::Ok { .. }));
+        assert!(matches!(read("src/nope.rs"), FileContent::Missing));
+        assert!(matches!(read("../../etc/passwd"), FileContent::Rejected { .. }));
+    }
+
+    #[test]
+    fn truncates_to_head_and_tail_at_char_boundaries() {
+        let text = format!("{}é{}", "a".repeat(9), "b".repeat(10));
+        match reader(10, 100).excerpt("notes.txt", text.into_bytes()) {
+            FileContent::Truncated { size, head, tail, omitted_bytes, .. } => {
+                assert_eq!(size, 21);
+                assert_eq!(head, "aaaaa");
+                assert_eq!(tail, "bbbbb");
+                assert_eq!(omitted_bytes, 11);
+            }
+            _ => panic!("expected truncated"),
+        }
+    }
+
+    #[test]
+    fn response_limit_spans_files() {
+        let mut reader = reader(10, 15);
+        assert!(matches!(reader.excerpt("notes.txt", b"0123456789".to_vec()), FileContent::Ok { .. }));
+        assert!(matches!(
+            reader.excerpt("notes.txt", b"0123456789".to_vec()),
+            FileContent::Truncated { omitted_bytes: 5, .. }
+        ));
+        assert!(matches!(reader.excerpt("notes.txt", b"x".to_vec()), FileContent::Omitted { size: 1 }));
+    }
+
+    #[test]
+    fn redacts_before_measuring() {
+        let mut reader = reader(100, 100);
+        match reader.excerpt("notes.txt", b"db_password = \"correct horse\"\n".to_vec()) {
+            FileContent::Ok { contents, redactions } => {
+                assert_eq!(contents, "db_password = \"[REDACTED]\"\n");
+                assert_eq!(redactions, 1);
+            }
+            _ => panic!("expected ok"),
+        }
+    }
+
+    #[test]
+    fn binary_files_report_size_and_hash() {
+        let mut reader = reader(10, 10);
+        for bytes in [vec![0xff, 0xfe, 0x00], b"PK\0\x03".to_vec()] {
+            match reader.excerpt("notes.txt", bytes.clone()) {
+                FileContent::Binary { size, sha256 } => {
+                    assert_eq!(size, bytes.len());
+                    assert_eq!(sha256.len(), 64);
+                }
+                _ => panic!("expected binary"),
+            }
+        }
+        assert!(matches!(reader.excerpt("notes.txt", b"text".to_vec()), FileContent::Ok { .. }));
+    }
+
+    #[test]
+    fn reads_only_the_excerpts_of_large_files() {
+        let root = std::env::temp_dir().join("sigil_file_content_test_large");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let filler = "x".repeat(3 * REDACTION_MARGIN);
+        let text = format!("password = \"correct horse battery\"\n{filler}\napi_key = \"abcdefgh\"\n");
+        std::fs::write(root.join("big.txt"), &text).unwrap();
+        let mut bytes = vec![0u8; 4 * REDACTION_MARGIN];
+        bytes[1] = 7;
+        std::fs::write(root.join("big.bin"), &bytes).unwrap();
+
+        let limits = LimitsConfig { max_file_bytes: 40, max_response_bytes: 100 };
+        let config = Config { limits, ..Config::default() };
+        let mut reader = Reader::new(&config, &root);
+        match reader.read("big.txt") {
+            FileContent::Truncated { size, head, tail, omitted_bytes, redactions } => {
+                assert_eq!(size, text.len());
+                assert_eq!(head, "password = \"[REDACTE");
+                assert_eq!(tail, "_key = \"[REDACTED]\"\n");
+                assert_eq!(omitted_bytes, size - 40);
+                assert_eq!(redactions, 2);
+            }
+            _ => panic!("expected truncated"),
+        }
+        match reader.read("big.bin") {
+            FileContent::Binary { size, sha256 } => {
+                assert_eq!(size, bytes.len());
+                assert_eq!(sha256, hex(&Sha256::digest(&bytes)));
+            }
+            _ => panic!("expected binary"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_symlinks_leaving_the_project() {
+        let base = std::env::temp_dir().join("sigil_file_content_test_symlink");
+        let _ = std::fs::remove_dir_all(&base);
+        let (root, outside) = (base.join("project"), base.join("outside"));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("secret.txt"), "secret").unwrap();
+        std::fs::write(root.join("inside.txt"), "inside").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        let mut reader = Reader::new(&Config::default(), &root);
+        assert!(matches!(reader.read("inside.txt"), FileContent::Ok { .. }));
+        assert!(matches!(reader.read("link/secret.txt"), FileContent::Rejected { .. }));
+    }
+}
@@ -0,0 +1,307 @@
+use toml_edit::{Array, DocumentMut, Item, Table, Value};
+
+/// Canonical key order of each table, following the field tables in the
+/// README. Unknown keys keep their relative order after the known ones.
+const TOP_LEVEL: &[&str] = &[
+    "id", "version", "name", "description", "priority", "status", "domain", "tags", "applies_to", "files", "notes",
+    "overridable", "trigger", "rules", "deprecation", "changelog",
+];
+const TRIGGER: &[&str] = &["type"];
+const RULE: &[&str] = &["id", "description", "files", "constraints", "checks", "tests"];
+const CHECK: &[&str] = &["kind", "pattern", "file", "path", "value", "paths"];
+const DEPRECATION: &[&str] = &["reason", "superseded_by", "date"];
+const CHANGELOG: &[&str] = &["version", "date", "description"];
+
+/// Top-level keys written as multi-line strings.
+const PROSE: &[&str] = &["description", "notes"];
+
+const INDENT: &str = "    ";
+
+/// Widest `key = ["element"]` line kept inline.
+const MAX_INLINE_WIDTH: usize = 80;
+
+/// Rewrite a contract file in the canonical layout: keys in a fixed order,
+/// `description` and `notes` as multi-line strings, tags sorted and unique,
+/// arrays of more than one element with one element per line, and one blank
+/// line before each table. Comments are kept with the key, table or array
+/// element they precede. Fails on invalid TOML, and refuses any rewrite that
+/// would change what the contract says.
+pub fn format(text: &str) -> Result<String, String> {
+    let mut doc: DocumentMut = text.parse().map_err(|e: toml_edit::TomlError| e.message().to_string())?;
+    let root = doc.as_table_mut();
+
+    if let Some(tags) = root.get_mut("tags").and_then(Item::as_array_mut) {
+        sort_unique(tags);
+    }
+    for key in PROSE {
+        if let Some(item) = root.get_mut(key) {
+            multi_line(item)?;
+        }
+    }
+    layout(root, TOP_LEVEL);
+    if let Some(trigger) = root.get_mut("trigger").and_then(Item::as_table_mut) {
+        layout(trigger, TRIGGER);
+    }
+    if let Some(deprecation) = root.get_mut("deprecation").and_then(Item::as_table_mut) {
+        layout(deprecation, DEPRECATION);
+    }
+    for entry in tables_mut(root, "changelog") {
+        layout(entry, CHANGELOG);
+    }
+    for rule in tables_mut(root, "rules") {
+        layout(rule, RULE);
+        for check in tables_mut(rule, "checks") {
+            layout(check, CHECK);
+        }
+    }
+
+    // Tables are written in document position order; number them in key order.
+    let mut position = 0;
+    number(root, &mut position);
+    let trailing = comment_lines(doc.trailing().as_str().unwrap_or_default())
+        .iter()
+        .map(|c| format!("{c}\n"))
+        .collect::<String>();
+    doc.set_trailing(trailing);
+
+    let output = format!("{}\n", doc.to_string().trim_matches('\n'));
+    if content(text, true)? != content(&output, false)? {
+        return Err("formatting would change the contract's content".to_string());
+    }
+    Ok(output)
+}
+
+/// The parsed values of a contract, with tags sorted when `normalize_tags`
+/// so the original can be compared with its formatted version.
+fn content(text: &str, normalize_tags: bool) -> Result<toml::Table, String> {
+    let mut table: toml::Table = toml::from_str(text).map_err(|e| e.message().to_string())?;
+    if normalize_tags && let Some(toml::Value::Array(tags)) = table.get_mut("tags") {
+        tags.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        tags.dedup();
+    }
+    Ok(table)
+}
+
+/// The tables of the array of tables at `key`, if there is one.
+fn tables_mut<'a>(table: &'a mut Table, key: &str) -> Vec<&'a mut Table> {
+    match table.get_mut(key) {
+        Some(Item::ArrayOfTables(array)) => array.iter_mut().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn rank(order: &[&str], key: &str) -> usize {
+    order.iter().position(|k| *k == key).unwrap_or(order.len())
+}
+
+/// Order the keys of `table` and normalize the whitespace around them.
+fn layout(table: &mut Table, order: &[&str]) {
+    table.sort_values_by(|a, _, b, _| rank(order, a.get()).cmp(&rank(order, b.get())));
+    let header = comment_lines(table.decor().prefix().and_then(|p| p.as_str()).unwrap_or_default());
+    let header: String = header.iter().map(|c| format!("{c}\n")).collect();
+    let suffix = trailing_comment(table.decor().suffix().and_then(|s| s.as_str()));
+    table.decor_mut().set_prefix(format!("\n{header}"));
+    table.decor_mut().set_suffix(suffix);
+
+    for (mut key, item) in table.iter_mut() {
+        let comments: String = comment_lines(key.leaf_decor().prefix().and_then(|p| p.as_str()).unwrap_or_default())
+            .iter()
+            .map(|c| format!("{c}\n"))
+            .collect();
+        // Keys of tables are written in their headers: `[trigger]`, not `[trigger ]`.
+        let is_value = matches!(item, Item::Value(_));
+        key.leaf_decor_mut().set_prefix(if is_value { comments } else { String::new() });
+        key.leaf_decor_mut().set_suffix(if is_value { " " } else { "" });
+        if let Item::Value(value) = item {
+            let suffix = trailing_comment(value.decor().suffix().and_then(|s| s.as_str()));
+            value.decor_mut().set_prefix(" ");
+            value.decor_mut().set_suffix(suffix);
+            if let Value::Array(array) = value {
+                one_per_line(array, key.get().len());
+            }
+        }
+    }
+}
+
+/// Lay out an array: inline when it has at most one element, no comments, and
+/// fits on a short line, otherwise one element per line. `key_width` is the
+/// length of the key the array is assigned to.
+fn one_per_line(array: &mut Array, key_width: usize) {
+    let prefixes: Vec<String> = array
+        .iter()
+        .map(|v| v.decor().prefix().and_then(|p| p.as_str()).unwrap_or_default().to_string())
+        .collect();
+    let trailing = array.trailing().as_str().unwrap_or_default().to_string();
+    let has_comments = prefixes.iter().chain([&trailing]).any(|p| p.contains('#'));
+
+    let width = key_width + " = []".len() + array.iter().map(|v| v.to_string().trim().len()).sum::<usize>();
+    if array.len() <= 1 && !has_comments && width <= MAX_INLINE_WIDTH {
+        for value in array.iter_mut() {
+            value.decor_mut().set_prefix("");
+            value.decor_mut().set_suffix("");
+        }
+        array.set_trailing("");
+        array.set_trailing_comma(false);
+        return;
+    }
+    for (value, prefix) in array.iter_mut().zip(&prefixes) {
+        value.decor_mut().set_prefix(format!("{}\n{INDENT}", element_comments(prefix)));
+        value.decor_mut().set_suffix("");
+    }
+    array.set_trailing(format!("{}\n", element_comments(&trailing)));
+    array.set_trailing_comma(true);
+}
+
+/// Comments in the whitespace before an array element: one on the line of the
+/// preceding comma stays there, the rest go on their own lines above it.
+fn element_comments(raw: &str) -> String {
+    let (same_line, rest) = raw.split_once('\n').unwrap_or((raw, ""));
+    let mut out = String::new();
+    if same_line.contains('#') {
+        out.push(' ');
+        out.push_str(same_line.trim());
+    }
+    for comment in comment_lines(rest) {
+        out.push_str(&format!("\n{INDENT}{comment}"));
+    }
+    out
+}
+
+fn comment_lines(raw: &str) -> Vec<&str> {
+    raw.lines().map(str::trim).filter(|l| l.starts_with('#')).collect()
+}
+
+/// A comment after a value or table header, kept on the same line.
+fn trailing_comment(raw: Option<&str>) -> String {
+    match raw.map(str::trim).filter(|s| s.starts_with('#')) {
+        Some(comment) => format!(" {comment}"),
+        None => String::new(),
+    }
+}
+
+fn sort_unique(array: &mut Array) {
+    let mut values: Vec<Value> = array.iter().cloned().collect();
+    values.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+    values.dedup_by(|a, b| a.as_str().is_some() && a.as_str() == b.as_str());
+    if values.len() == array.len() && values.iter().zip(array.iter()).all(|(a, b)| a.as_str() == b.as_str()) {
+        return;
+    }
+    array.clear();
+    for value in values {
+        array.push_formatted(value);
+    }
+}
+
+/// Rewrite a single-line string as a `"""` string starting on its own line.
+/// Strings that already span lines keep their wrapping.
+fn multi_line(item: &mut Item) -> Result<(), String> {
+    let Some(Value::String(string)) = item.as_value() else {
+        return Ok(());
+    };
+    let raw = string.as_repr().and_then(|r| r.as_raw().as_str()).unwrap_or_default();
+    if raw.starts_with("\"\"\"") || raw.starts_with("'''") {
+        return Ok(());
+    }
+    let text = string.value();
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' | '\t' => escaped.push(c),
+            '\r' => escaped.push_str("\\r"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    let escaped = escaped.replace("\"\"\"", "\"\"\\\"");
+    // A line-ending backslash drops the newline, so the value is unchanged.
+    let end = if text.ends_with('\n') { "" } else { "\\\n" };
+    let mut value: Value = format!("\"\"\"\n{escaped}{end}\"\"\"").parse().map_err(|e: toml_edit::TomlError| e.to_string())?;
+    *value.decor_mut() = string.decor().clone();
+    *item = Item::Value(value);
+    Ok(())
+}
+
+fn number(table: &mut Table, position: &mut usize) {
+    table.set_position(*position);
+    *position += 1;
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(child) => number(child, position),
+            Item::ArrayOfTables(array) => {
+                for child in array.iter_mut() {
+                    number(child, position);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_keys_and_tables_and_keeps_comments() {
+        let source = r#"name = "N"   # shown in listings
+# identity
+id = "c"
+version = "1.0.0"
+description = "d"
+[[rules]]
+description = "d"
+# rule comment
+id = "r"
+[[rules.checks]]
+pattern = "x"
+kind = "regex_must_match"
+[trigger]
+tool = "t"
+type = "mcp-tool-call"
+"#;
+        let formatted = format(source).unwrap();
+        assert_eq!(
+            formatted,
+            r#"# identity
+id = "c"
+version = "1.0.0"
+name = "N" # shown in listings
+description = """
+d\
+"""
+
+[trigger]
+type = "mcp-tool-call"
+tool = "t"
+
+[[rules]]
+# rule comment
+id = "r"
+description = "d"
+
+[[rules.checks]]
+kind = "regex_must_match"
+pattern = "x"
+"#
+        );
+        assert_eq!(format(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn sorts_tags_and_puts_array_elements_on_their_own_lines() {
+        let text = "id = \"c\"\ntags = [\"b\", \"a\", \"b\"]\nfiles = [\"src/a.rs\", # main\n  \"src/b.rs\"]\napplies_to = [\n    \"src/**\",\n]\n";
+        let formatted = format(text).unwrap();
+        assert_eq!(
+            formatted,
+            "id = \"c\"\ntags = [\n    \"a\",\n    \"b\",\n]\napplies_to = [\"src/**\"]\nfiles = [\n    \"src/a.rs\", # main\n    \"src/b.rs\",\n]\n"
+        );
+    }
+
+    #[test]
+    fn keeps_hand_wrapped_prose_and_rejects_invalid_toml() {
+        let text = "id = \"c\"\ndescription = \"\"\"\nWrapped \\\nby hand.\n\"\"\"\n";
+        assert_eq!(format(text).unwrap(), text);
+        assert!(format("id = ").is_err());
+    }
+}
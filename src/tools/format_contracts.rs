@@ -0,0 +1,103 @@
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {
+    /// Format only this contract. Omit to format every contract file.
+    pub contract_id: Option<String>,
+    /// When true, report the files that are not formatted without rewriting them.
+    pub check: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    check: bool,
+    /// Files rewritten, or in check mode the files that would be.
+    changed: Vec<String>,
+    unchanged: usize,
+    /// Files that could not be formatted, left as they are.
+    errors: Vec<FileError>,
+}
+
+#[derive(Serialize)]
+struct FileError {
+    file: String,
+    message: String,
+}
+
+pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    let check = params.check.unwrap_or(false);
+    let mut files = match &params.contract_id {
+        Some(id) => {
+            let (contracts, _) = super::loader::load_contracts(&server.config.contracts_dir);
+            match super::loader::find_unique(&contracts, id) {
+                Ok(Some(contract)) => contract.source.clone().into_iter().collect(),
+                Ok(None) => return super::error_response(format!("Contract '{id}' not found")),
+                Err(e) => return super::error_response(e),
+            }
+        }
+        None => super::loader::contract_files(&server.config.contracts_dir),
+    };
+    files.sort();
+
+    let mut changed = Vec::new();
+    let mut unchanged = 0;
+    let mut errors = Vec::new();
+    for file in files {
+        let result = std::fs::read_to_string(&file).map_err(|e| e.to_string()).and_then(|text| {
+            let formatted = super::format::format(&text)?;
+            Ok((formatted != text).then_some(formatted))
+        });
+        match result {
+            Ok(None) => unchanged += 1,
+            Ok(Some(formatted)) => {
+                if !check && let Err(e) = std::fs::write(&file, formatted) {
+                    errors.push(FileError { file, message: e.to_string() });
+                    continue;
+                }
+                changed.push(file);
+            }
+            Err(message) => errors.push(FileError { file, message }),
+        }
+    }
+
+    serde_json::to_string(&Response { check, changed, unchanged, errors }).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::fs;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_format_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn check_mode_reports_without_rewriting() {
+        let dir = temp_dir("check");
+        let messy = "name = \"A\"\nid = \"a\"\nversion = \"1.0.0\"\ndescription = \"d\"\n";
+        fs::write(dir.join("a.contract.toml"), messy).unwrap();
+        fs::write(dir.join("broken.contract.toml"), "id = ").unwrap();
+        let server = super::super::SigilServer::new(Config {
+            contracts_dir: dir.to_str().unwrap().to_string(),
+            ..Config::default()
+        });
+
+        let result = handle(&server, Params { contract_id: None, check: Some(true) }).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["changed"].as_array().unwrap().len(), 1, "{result}");
+        assert_eq!(json["errors"][0]["file"].as_str().unwrap(), dir.join("broken.contract.toml").to_str().unwrap());
+        assert_eq!(fs::read_to_string(dir.join("a.contract.toml")).unwrap(), messy);
+
+        handle(&server, Params { contract_id: Some("a".to_string()), check: None }).await;
+        let result = handle(&server, Params { contract_id: Some("a".to_string()), check: Some(true) }).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!((json["changed"].as_array().unwrap().len(), json["unchanged"].as_u64()), (0, Some(1)));
+        assert!(fs::read_to_string(dir.join("a.contract.toml")).unwrap().starts_with("id = \"a\"\n"));
+    }
+}
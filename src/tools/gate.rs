@@ -0,0 +1,363 @@
+use super::overrides::{self, OverrideReport};
+use super::reviews::{self, ReviewSession, Verdict};
+use super::validation::{self, Issue, Report};
+use crate::config::{Config, Enforcement};
+use crate::model::{Contract, Priority, Status};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    Pass,
+    Fail,
+}
+
+/// What the gate made of one contract.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// No validation errors and no failing verdict.
+    Pass,
+    /// A problem in a blocking contract; fails the gate.
+    Fail,
+    /// A problem reported as a warning.
+    Warn,
+    /// A problem reported for information only.
+    Info,
+    /// A problem in a contract that is not enforced.
+    Ignored,
+    /// A problem that would have failed the gate, waived by an accepted override.
+    Overridden,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContractGate {
+    pub id: String,
+    pub priority: Priority,
+    pub status: Status,
+    pub enforcement: Enforcement,
+    pub validation_errors: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<Verdict>,
+    pub outcome: Outcome,
+    pub explanation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub override_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GateReport {
+    pub decision: Decision,
+    pub exit_code: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_id: Option<String>,
+    pub contracts: Vec<ContractGate>,
+    /// Validation errors not attributed to any loaded contract, e.g. waivers
+    /// or annotations naming an unknown contract. These always fail the gate.
+    pub unattributed_errors: Vec<Issue>,
+    /// Contract files that could not be loaded. They fail the gate unless
+    /// `gate.load_errors` in the config says otherwise.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub load_failures: Vec<Issue>,
+    #[serde(skip_serializing_if = "OverrideReport::is_empty")]
+    pub overrides: OverrideReport,
+    pub validation: Report,
+    pub warnings: Vec<String>,
+}
+
+/// Combine validation issues and (optionally) the verdicts of a review session
+/// into a gate decision, applying the priority/status mapping from config.
+/// `override_text` may contain `sigil-override:` directives (e.g. PR comments);
+/// accepted overrides turn a failing contract into an overridden one.
+pub fn evaluate(
+    config: &Config,
+    root: &Path,
+    review_id: Option<&str>,
+    branch: Option<&str>,
+    override_text: Option<&str>,
+) -> Result<GateReport, String> {
+    let session = match review_id {
+        Some(id) => Some(reviews::load(&config.reviews_dir, id)?),
+        None => None,
+    };
+    let validation = validation::validate_all(config, root);
+    let (contracts, _) = super::loader::load_contracts(&config.contracts_dir);
+    let mut report = decide(config, validation, &contracts, session.as_ref(), branch, override_text);
+    report.review_id = review_id.map(str::to_string);
+    Ok(report)
+}
+
+/// The gate decision for already loaded contracts, their validation report
+/// and the review session, if any.
+fn decide(
+    config: &Config,
+    validation: Report,
+    contracts: &[Contract],
+    session: Option<&ReviewSession>,
+    branch: Option<&str>,
+    override_text: Option<&str>,
+) -> GateReport {
+    let overrides = override_text
+        .map(|text| overrides::resolve(text, contracts))
+        .unwrap_or_default();
+    let mut warnings = Vec::new();
+
+    let mut results = Vec::new();
+    for contract in contracts {
+        let enforcement = config.gate.enforcement(&contract.priority, &contract.status, branch);
+        let validation_errors = validation
+            .errors
+            .iter()
+            .filter(|i| i.contract_id.as_deref() == Some(contract.id.as_str()))
+            .count();
+        let verdict = session.and_then(|s| {
+            s.verdicts
+                .iter()
+                .filter(|v| v.contract_id == contract.id)
+                .map(|v| v.verdict)
+                .max()
+        });
+
+        let mut problems = Vec::new();
+        if validation_errors > 0 {
+            problems.push(format!(
+                "{validation_errors} validation error{}",
+                if validation_errors == 1 { "" } else { "s" }
+            ));
+        }
+        match verdict {
+            Some(Verdict::Fail) => problems.push("verdict fail".to_string()),
+            Some(Verdict::NeedsHumanReview) => problems.push("verdict needs_human_review".to_string()),
+            _ => {}
+        }
+
+        let mut override_reason = None;
+        let (outcome, explanation) = if problems.is_empty() {
+            let reason = match verdict {
+                Some(_) => "no validation errors and verdict pass",
+                None => "no validation errors",
+            };
+            (Outcome::Pass, reason.to_string())
+        } else {
+            let outcome = match enforcement {
+                Enforcement::Block => Outcome::Fail,
+                Enforcement::Warn => Outcome::Warn,
+                Enforcement::Info => Outcome::Info,
+                Enforcement::Ignore => Outcome::Ignored,
+            };
+            let mut explanation = format!(
+                "{}; priority {} with status {} maps to {}",
+                problems.join(", "),
+                super::label(&contract.priority),
+                super::label(&contract.status),
+                super::label(&enforcement)
+            );
+            match overrides.reason_for(&contract.id) {
+                Some(reason) if outcome == Outcome::Fail => {
+                    explanation.push_str(&format!("; overridden: {reason}"));
+                    override_reason = Some(reason.to_string());
+                    (Outcome::Overridden, explanation)
+                }
+                _ => (outcome, explanation),
+            }
+        };
+
+        results.push(ContractGate {
+            id: contract.id.clone(),
+            priority: contract.priority.clone(),
+            status: contract.status.clone(),
+            enforcement,
+            validation_errors,
+            verdict,
+            outcome,
+            explanation,
+            override_reason,
+        });
+    }
+
+    if let Some(s) = session {
+        for v in &s.verdicts {
+            if !contracts.iter().any(|c| c.id == v.contract_id) {
+                warnings.push(format!(
+                    "Verdict recorded for unknown contract '{}' was ignored",
+                    v.contract_id
+                ));
+            }
+        }
+    }
+
+    let unattributed_errors: Vec<Issue> = super::report::unattributed(contracts, &validation.errors)
+        .into_iter()
+        .cloned()
+        .collect();
+    let load_failures: Vec<Issue> = validation
+        .warnings
+        .iter()
+        .filter(|i| i.kind == "load_warning")
+        .cloned()
+        .collect();
+    let load_enforcement = config.gate.load_errors;
+    if load_enforcement == Enforcement::Warn {
+        for issue in &load_failures {
+            let source = issue.source.as_deref().unwrap_or("contract file");
+            warnings.push(format!("'{source}' could not be loaded: {}", issue.message));
+        }
+    }
+
+    let failed = !unattributed_errors.is_empty()
+        || (load_enforcement == Enforcement::Block && !load_failures.is_empty())
+        || results.iter().any(|r| r.outcome == Outcome::Fail);
+    let decision = if failed { Decision::Fail } else { Decision::Pass };
+
+    GateReport {
+        decision,
+        exit_code: if failed { 1 } else { 0 },
+        branch: branch.map(str::to_string),
+        review_id: None,
+        contracts: results,
+        unattributed_errors,
+        load_failures,
+        overrides,
+        validation,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GateConfig;
+    use std::fs;
+
+    const TAG: &str = "sigil:";
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_gate_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("contracts")).unwrap();
+        dir
+    }
+
+    /// A contract that always fails validation because its file is missing.
+    fn write_broken(dir: &std::path::Path, id: &str, priority: &str, status: &str) {
+        let content = format!(
+            "id = \"{id}\"\nversion = \"1.0.0\"\nname = \"{id}\"\ndescription = \"desc\"\npriority = \"{priority}\"\nstatus = \"{status}\"\nfiles = [\"does/not/exist.rs\"]\n"
+        );
+        fs::write(dir.join("contracts").join(format!("{id}.contract.toml")), content).unwrap();
+    }
+
+    fn config(dir: &std::path::Path, gate: GateConfig) -> Config {
+        Config {
+            contracts_dir: dir.join("contracts").display().to_string(),
+            reviews_dir: dir.join("reviews").display().to_string(),
+            gate,
+            ..Config::default()
+        }
+    }
+
+    fn outcome(report: &GateReport, id: &str) -> Outcome {
+        report.contracts.iter().find(|c| c.id == id).unwrap().outcome
+    }
+
+    #[test]
+    fn must_blocks_should_warns_prefer_informs() {
+        let dir = temp_dir("default_mapping");
+        write_broken(&dir, "a-must", "must", "active");
+        write_broken(&dir, "b-should", "should", "active");
+        write_broken(&dir, "c-prefer", "prefer", "active");
+        let report = evaluate(&config(&dir, GateConfig::default()), &dir, None, None, None).unwrap();
+        assert_eq!(report.decision, Decision::Fail);
+        assert_eq!(report.exit_code, 1);
+        assert_eq!(outcome(&report, "a-must"), Outcome::Fail);
+        assert_eq!(outcome(&report, "b-should"), Outcome::Warn);
+        assert_eq!(outcome(&report, "c-prefer"), Outcome::Info);
+    }
+
+    #[test]
+    fn draft_and_deprecated_never_block_by_default() {
+        let dir = temp_dir("status");
+        write_broken(&dir, "a-draft", "must", "draft");
+        write_broken(&dir, "b-deprecated", "must", "deprecated");
+        let report = evaluate(&config(&dir, GateConfig::default()), &dir, None, None, None).unwrap();
+        assert_eq!(report.decision, Decision::Pass);
+        assert_eq!(outcome(&report, "a-draft"), Outcome::Ignored);
+        assert_eq!(outcome(&report, "b-deprecated"), Outcome::Warn);
+    }
+
+    #[test]
+    fn config_can_make_should_block() {
+        let dir = temp_dir("should_blocks");
+        write_broken(&dir, "b-should", "should", "active");
+        let gate = GateConfig { should: Enforcement::Block, ..GateConfig::default() };
+        let report = evaluate(&config(&dir, gate), &dir, None, None, None).unwrap();
+        assert_eq!(report.decision, Decision::Fail);
+        assert!(report.contracts[0].explanation.contains("maps to block"));
+    }
+
+    #[test]
+    fn accepted_override_waives_blocking_failure() {
+        let dir = temp_dir("override");
+        write_broken(&dir, "a-must", "must", "active");
+        let text = "sigil-override: a-must -- \"fixture lands in follow-up PR\"";
+        let report = evaluate(&config(&dir, GateConfig::default()), &dir, None, None, Some(text)).unwrap();
+        assert_eq!(report.decision, Decision::Pass);
+        assert_eq!(outcome(&report, "a-must"), Outcome::Overridden);
+        assert_eq!(
+            report.contracts[0].override_reason.as_deref(),
+            Some("fixture lands in follow-up PR")
+        );
+    }
+
+    #[test]
+    fn unloadable_contracts_and_unknown_contract_ids_fail_the_gate() {
+        let dir = temp_dir("unattributed");
+        fs::write(
+            dir.join("contracts/broken.contract.toml"),
+            "id = \"broken\"\npriority = \"must\"\nversion = ",
+        )
+        .unwrap();
+        let report = evaluate(&config(&dir, GateConfig::default()), &dir, None, None, None).unwrap();
+        assert_eq!(report.decision, Decision::Fail, "A contract that does not load must not vanish");
+        assert_eq!(report.load_failures.len(), 1);
+
+        let gate = GateConfig { load_errors: Enforcement::Warn, ..GateConfig::default() };
+        let report = evaluate(&config(&dir, gate.clone()), &dir, None, None, None).unwrap();
+        assert_eq!(report.decision, Decision::Pass);
+        assert_eq!(report.warnings.len(), 1, "{:?}", report.warnings);
+
+        // A waiver naming an unknown contract is attributed to no loaded contract
+        fs::write(
+            dir.join("contracts/waivers.toml"),
+            "[[waivers]]\ncontract_id = \"ghost\"\nreason = \"r\"\nowner = \"o\"\nexpires = \"2999-01-01\"\n",
+        )
+        .unwrap();
+        let report = evaluate(&config(&dir, gate), &dir, None, None, None).unwrap();
+        assert_eq!(report.decision, Decision::Fail);
+        assert_eq!(report.unattributed_errors.len(), 1);
+        assert_eq!(report.unattributed_errors[0].contract_id.as_deref(), Some("ghost"));
+    }
+
+    #[test]
+    fn annotation_naming_an_unknown_contract_fails_the_gate() {
+        let dir = temp_dir("annotation");
+        write_broken(&dir, "a-draft", "must", "draft");
+        let config = config(&dir, GateConfig::default());
+        let (contracts, _) = crate::tools::loader::load_contracts(&config.contracts_dir);
+        let text = format!("// {TAG} ghost-contract\nfn handler() {{}}\n// {TAG} a-draft/no-such-rule\nfn other() {{}}\n");
+        let markers = crate::tools::markers::scan_text("src/handler.rs", &text);
+        let validation = Report {
+            errors: validation::annotation_issues(markers, &contracts),
+            ..Report::default()
+        };
+        assert_eq!(validation.errors.len(), 2);
+
+        let report = decide(&config, validation, &contracts, None, None, None);
+        assert_eq!(report.decision, Decision::Fail);
+        assert_eq!(report.unattributed_errors.len(), 1, "Only the unknown contract is unattributed");
+        assert_eq!(report.unattributed_errors[0].contract_id.as_deref(), Some("ghost-contract"));
+        assert_eq!(outcome(&report, "a-draft"), Outcome::Ignored, "Unknown rules count against their contract");
+    }
+}
@@ -1,14 +1,26 @@
-use crate::model::{Priority, Status};
+use super::imports::{Graph, Reached};
+use super::markers::{self, Marker};
+use crate::model::{Priority, Status, Waiver};
 use globset::Glob;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct Params {
-    /// File paths to check against contract files and applies_to glob patterns.
+    /// File paths to check against contract files, applies_to glob patterns and
+    /// `sigil:` annotations inside the files.
     pub files: Vec<String>,
+    /// When true, also match contracts of files that import the given files,
+    /// directly or through other imports (Rust `mod`/`use`, TypeScript and
+    /// JavaScript `import`/`require`, Python `import`).
+    pub include_indirect: Option<bool>,
+    /// How many imports to follow with include_indirect. Defaults to 2.
+    pub max_hops: Option<usize>,
 }
 
+/// Import hops followed when `max_hops` is not given.
+const DEFAULT_MAX_HOPS: usize = 2;
+
 #[derive(Serialize)]
 struct Response {
     contracts: Vec<AffectedSummary>,
@@ -28,13 +40,40 @@ struct AffectedSummary {
     tags: Option<Vec<String>>,
     trigger_type: Option<String>,
     file_count: usize,
+    /// True when the contract only matched through imports.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    indirect: bool,
     matched_files: MatchedFiles,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    waivers: Vec<WaiverMatch>,
+}
+
+/// A waiver that covers at least one of the matched files.
+#[derive(Serialize)]
+struct WaiverMatch {
+    #[serde(flatten)]
+    waiver: Waiver,
+    expired: bool,
 }
 
 #[derive(Serialize)]
 struct MatchedFiles {
     direct: Vec<String>,
     applies_to: Vec<AppliesMatch>,
+    /// `sigil:` annotations naming the contract in the given files, with the
+    /// lines of the block each one governs.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    annotations: Vec<Marker>,
+    /// Contract files that import a given file, with the import chain from
+    /// the given file to them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    indirect: Vec<IndirectMatch>,
+}
+
+#[derive(Serialize)]
+struct IndirectMatch {
+    file: String,
+    chain: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -46,9 +85,22 @@ struct AppliesMatch {
 pub async fn handle(server: &super::SigilServer, params: Params) -> String {
     let (contracts, mut warnings) = super::loader::load_contracts(&server.config.contracts_dir);
     server.mark_listed();
+    let (waivers, waiver_error) = super::loader::load_waivers(&server.config.contracts_dir);
+    warnings.extend(waiver_error.map(|e| e.to_string()));
+    let today = chrono::Local::now().date_naive();
 
     // Normalize input files (forward slashes)
     let files: Vec<String> = params.files.iter().map(|f| f.replace('\\', "/")).collect();
+    let annotations = markers::scan(server.root(), &files, &server.config.contracts_dir);
+    let reached: Vec<Reached> = if params.include_indirect.unwrap_or(false) {
+        let max_hops = params.max_hops.unwrap_or(DEFAULT_MAX_HOPS);
+        if max_hops == 0 {
+            return super::error_response("max_hops must be at least 1");
+        }
+        Graph::build(server.root(), &super::repo_files::list(server.root())).reach(&files, max_hops)
+    } else {
+        Vec::new()
+    };
 
     let mut summaries = Vec::new();
 
@@ -64,10 +116,12 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
 
         // Glob (applies_to) matches
         let mut applies_to_matches = Vec::new();
+        let mut matchers = Vec::new();
         for pattern in contract.applies_to_patterns() {
             match Glob::new(pattern) {
                 Ok(glob) => {
                     let matcher = glob.compile_matcher();
+                    matchers.push(matcher.clone());
                     let matched_files: Vec<String> = files
                         .iter()
                         .filter(|f| matcher.is_match(f.as_str()))
@@ -89,11 +143,36 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
             }
         }
 
-        if direct.is_empty() && applies_to_matches.is_empty() {
+        let annotated: Vec<Marker> = annotations.iter().filter(|m| m.contract_id == contract.id).cloned().collect();
+
+        // Files importing a given file, matched by files or applies_to
+        let indirect: Vec<IndirectMatch> = reached
+            .iter()
+            .filter(|r| contract_files.contains(&r.file.as_str()) || matchers.iter().any(|m| m.is_match(&r.file)))
+            .map(|r| IndirectMatch { file: r.file.clone(), chain: r.chain.clone() })
+            .collect();
+
+        let only_indirect = direct.is_empty() && applies_to_matches.is_empty() && annotated.is_empty();
+        if only_indirect && indirect.is_empty() {
             continue;
         }
 
         let file_count = contract.all_files().len();
+        let matched: Vec<&str> = direct
+            .iter()
+            .chain(applies_to_matches.iter().flat_map(|m| &m.matched_files))
+            .chain(annotated.iter().map(|m| &m.file))
+            .chain(indirect.iter().map(|m| &m.file))
+            .map(String::as_str)
+            .collect();
+        let waivers = waivers
+            .iter()
+            .filter(|w| w.contract_id == contract.id && waiver_covers(w, &matched))
+            .map(|w| WaiverMatch {
+                waiver: w.clone(),
+                expired: w.is_expired(today),
+            })
+            .collect();
 
         summaries.push(AffectedSummary {
             id: contract.id.clone(),
@@ -106,10 +185,14 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
             tags: contract.tags.clone(),
             trigger_type: contract.trigger.as_ref().and_then(|t| t.kind.clone()),
             file_count,
+            indirect: only_indirect,
             matched_files: MatchedFiles {
                 direct,
                 applies_to: applies_to_matches,
+                annotations: annotated,
+                indirect,
             },
+            waivers,
         });
     }
 
@@ -122,6 +205,19 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
     .unwrap()
 }
 
+/// A waiver without paths covers the whole contract; otherwise one of its
+/// patterns must match a file that matched the contract.
+fn waiver_covers(waiver: &Waiver, files: &[&str]) -> bool {
+    let Some(paths) = &waiver.paths else {
+        return true;
+    };
+    paths
+        .iter()
+        .filter_map(|p| Glob::new(p).ok())
+        .map(|g| g.compile_matcher())
+        .any(|m| files.iter().any(|f| m.is_match(f)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,11 +227,14 @@ mod tests {
     fn make_server(contracts_dir: &str) -> super::super::SigilServer {
         super::super::SigilServer::new(Config {
             contracts_dir: contracts_dir.to_string(),
-            instructions: None,
-            notes: None,
+            ..Config::default()
         })
     }
 
+    fn params(files: &[&str]) -> Params {
+        Params { files: files.iter().map(|f| f.to_string()).collect(), include_indirect: None, max_hops: None }
+    }
+
     fn temp_dir(tag: &str) -> std::path::PathBuf {
         let dir = std::env::temp_dir().join(format!("sigil_affected_test_{tag}"));
         let _ = fs::remove_dir_all(&dir);
@@ -172,7 +271,7 @@ mod tests {
         let dir = temp_dir("no_match");
         write_with_files(&dir, "contract-a", &["src/foo.rs"]);
         let server = make_server(dir.to_str().unwrap());
-        let result = handle(&server, Params { files: vec!["src/bar.rs".to_string()] }).await;
+        let result = handle(&server, params(&["src/bar.rs"])).await;
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(json["total"], 0);
     }
@@ -182,7 +281,7 @@ mod tests {
         let dir = temp_dir("direct");
         write_with_files(&dir, "contract-a", &["src/foo.rs", "src/bar.rs"]);
         let server = make_server(dir.to_str().unwrap());
-        let result = handle(&server, Params { files: vec!["src/foo.rs".to_string()] }).await;
+        let result = handle(&server, params(&["src/foo.rs"])).await;
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(json["total"], 1);
         assert_eq!(json["contracts"][0]["id"], "contract-a");
@@ -195,7 +294,7 @@ mod tests {
         let dir = temp_dir("exact");
         write_with_files(&dir, "contract-a", &["src/foo.rs"]);
         let server = make_server(dir.to_str().unwrap());
-        let result = handle(&server, Params { files: vec!["src/foo".to_string()] }).await;
+        let result = handle(&server, params(&["src/foo"])).await;
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(json["total"], 0, "Direct match must be exact path comparison");
     }
@@ -205,7 +304,7 @@ mod tests {
         let dir = temp_dir("glob");
         write_with_applies_to(&dir, "contract-a", "src/**/*.rs");
         let server = make_server(dir.to_str().unwrap());
-        let result = handle(&server, Params { files: vec!["src/tools/mod.rs".to_string()] }).await;
+        let result = handle(&server, params(&["src/tools/mod.rs"])).await;
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(json["total"], 1);
         let applies = &json["contracts"][0]["matched_files"]["applies_to"];
@@ -217,8 +316,85 @@ mod tests {
         let dir = temp_dir("wildcard");
         write_with_applies_to(&dir, "global-contract", "**");
         let server = make_server(dir.to_str().unwrap());
-        let result = handle(&server, Params { files: vec!["anything/at/all.txt".to_string()] }).await;
+        let result = handle(&server, params(&["anything/at/all.txt"])).await;
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(json["total"], 1);
     }
+
+    #[tokio::test]
+    async fn surfaces_waivers_covering_matched_files() {
+        let dir = temp_dir("waivers");
+        write_with_applies_to(&dir, "contract-a", "src/**");
+        fs::write(
+            dir.join("waivers.toml"),
+            r#"
+[[waivers]]
+contract_id = "contract-a"
+paths = ["src/legacy/**"]
+reason = "legacy module"
+owner = "platform"
+expires = "2000-01-01"
+
+[[waivers]]
+contract_id = "contract-a"
+paths = ["src/other/**"]
+reason = "not matched"
+owner = "platform"
+expires = "2999-01-01"
+"#,
+        )
+        .unwrap();
+        let server = make_server(dir.to_str().unwrap());
+        let result = handle(&server, params(&["src/legacy/old.rs"])).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let waivers = json["contracts"][0]["waivers"].as_array().unwrap();
+        assert_eq!(waivers.len(), 1);
+        assert_eq!(waivers[0]["reason"], "legacy module");
+        assert_eq!(waivers[0]["expired"], true);
+    }
+
+    #[tokio::test]
+    async fn matches_by_source_annotation() {
+        let dir = temp_dir("annotation");
+        write_with_files(&dir, "contract-a", &["src/foo.rs"]);
+        write_with_files(&dir, "contract-b", &["src/foo.rs"]);
+        let source = "src/handler.rs";
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join(source), format!("fn a() {{}}\n\n// {} contract-a\nfn b() {{\n    a();\n}}\n", "sigil:")).unwrap();
+        let mut server = make_server(dir.to_str().unwrap());
+        server.root = dir.clone();
+        let result = handle(&server, params(&[source])).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["total"], 1, "{result}");
+        let annotation = &json["contracts"][0]["matched_files"]["annotations"][0];
+        assert_eq!(annotation["file"], source);
+        assert_eq!(annotation["start_line"], 4);
+        assert_eq!(annotation["end_line"], 6);
+    }
+
+    #[tokio::test]
+    async fn matches_importers_when_indirect() {
+        let dir = temp_dir("indirect");
+        write_with_files(&dir, "handler", &["src/handler.rs"]);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "mod handler;\nmod parse;\n").unwrap();
+        fs::write(dir.join("src/handler.rs"), "use crate::parse::parse;\n").unwrap();
+        fs::write(dir.join("src/parse.rs"), "pub fn parse() {}\n").unwrap();
+        let mut server = make_server(dir.to_str().unwrap());
+        server.root = dir.clone();
+        let result = handle(&server, params(&["src/parse.rs"])).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["total"], 0);
+
+        let indirect = Params { include_indirect: Some(true), ..params(&["src/parse.rs"]) };
+        let result = handle(&server, indirect).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["total"], 1, "{result}");
+        assert_eq!(json["contracts"][0]["indirect"], true);
+        let chain = &json["contracts"][0]["matched_files"]["indirect"][0]["chain"];
+        assert_eq!(chain, &serde_json::json!(["src/parse.rs", "src/handler.rs"]));
+
+        let zero = Params { include_indirect: Some(true), max_hops: Some(0), ..params(&["src/parse.rs"]) };
+        assert!(handle(&server, zero).await.contains("error"));
+    }
 }
@@ -1,3 +1,4 @@
+use super::file_content::{self, FileContent};
 use crate::model::Contract;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
@@ -19,42 +20,26 @@ struct Response {
     warnings: Vec<String>,
 }
 
-#[derive(Serialize)]
-#[serde(tag = "status", rename_all = "snake_case")]
-enum FileContent {
-    Ok { contents: String },
-    Missing,
-    Error { message: String },
-}
-
 pub async fn handle(server: &super::SigilServer, params: Params) -> String {
     if let Err(e) = server.require_listed("sigil_get_contract", &params.contract_id) {
         return e;
     }
 
     let (contracts, mut warnings) = super::loader::load_contracts(&server.config.contracts_dir);
-    let Some(contract) = contracts.into_iter().find(|c| c.id == params.contract_id) else {
-        return super::error_response(format!("Contract '{}' not found", params.contract_id));
+    let contract = match super::loader::find_unique(&contracts, &params.contract_id) {
+        Ok(Some(c)) => c.clone(),
+        Ok(None) => return super::error_response(format!("Contract '{}' not found", params.contract_id)),
+        Err(e) => return super::error_response(e),
     };
 
     server.mark_read(&params.contract_id);
 
     let file_contents = if params.retrieve_file_contents == Some(true) {
         let mut map = HashMap::new();
+        let mut reader = file_content::Reader::new(&server.config, server.root());
         for path in contract.all_files() {
-            let resolved = match std::fs::read_to_string(path) {
-                Ok(contents) => FileContent::Ok { contents },
-                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                    warnings.push(format!("Missing file: '{path}'"));
-                    FileContent::Missing
-                }
-                Err(e) => {
-                    warnings.push(format!("Error reading file '{path}': {e}"));
-                    FileContent::Error {
-                        message: e.to_string(),
-                    }
-                }
-            };
+            let resolved = reader.read(path);
+            warnings.extend(resolved.warning(path));
             map.insert(path.to_string(), resolved);
         }
         Some(map)
@@ -0,0 +1,77 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Read a file as it existed at `git_ref`. `path` is relative to the current
+/// directory. Returns `Ok(None)` when the file does not exist at that ref.
+pub fn show_file(git_ref: &str, path: &str) -> Result<Option<String>, String> {
+    check_ref(git_ref)?;
+    let path = path.trim_start_matches("./");
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!("{git_ref}:./{path}"))
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    if output.status.success() {
+        return String::from_utf8(output.stdout)
+            .map(Some)
+            .map_err(|e| format!("'{path}' at '{git_ref}' is not valid UTF-8: {e}"));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("does not exist") || stderr.contains("exists on disk, but not in") {
+        Ok(None)
+    } else {
+        Err(format!("git show {git_ref}:{path} failed: {}", stderr.trim()))
+    }
+}
+
+/// List files under `dir` at `git_ref`, relative to the current directory.
+pub fn list_files(git_ref: &str, dir: &str) -> Result<Vec<String>, String> {
+    check_ref(git_ref)?;
+    let output = Command::new("git")
+        .args(["ls-tree", "-r", "--name-only", git_ref, "--", dir])
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git ls-tree {git_ref} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Tracked and untracked (but not ignored) files in the working tree under
+/// `root`, relative to it. Fails outside a git repository.
+pub fn working_tree_files(root: &Path) -> Result<Vec<String>, String> {
+    let output = Command::new("git")
+        .args(["ls-files", "--cached", "--others", "--exclude-standard"])
+        .current_dir(root)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git ls-files failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Refs are passed straight to git, so reject anything that could be parsed as an option.
+fn check_ref(git_ref: &str) -> Result<(), String> {
+    if git_ref.is_empty() || git_ref.starts_with('-') {
+        Err(format!("Invalid git ref: '{git_ref}'"))
+    } else {
+        Ok(())
+    }
+}
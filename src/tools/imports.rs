@@ -0,0 +1,412 @@
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::LazyLock;
+
+static RUST_MOD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_][A-Za-z0-9_]*)\s*;").unwrap());
+static RUST_USE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?use\s+([^;]+);").unwrap());
+/// `import ... from './x'`, `export ... from './x'`, `import './x'`,
+/// `import('./x')` and `require('./x')`. Only relative specifiers resolve to
+/// project files; packages are left out.
+static JS_IMPORT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:\bfrom\s*|\bimport\s*\(?\s*|\brequire\s*\(\s*)["'](\.{1,2}/[^"']*)["']"#).unwrap()
+});
+static PY_FROM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[ \t]*from[ \t]+(\.*)([\w.]*)[ \t]+import[ \t]+\(?([\w., \t]+)").unwrap());
+/// `import a.b, c as d`, up to an optional trailing `#` comment.
+static PY_IMPORT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[ \t]*import[ \t]+([\w., \t]+?)[ \t]*(?:#.*)?\r?$").unwrap());
+
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// A static import graph over the project's Rust, TypeScript/JavaScript and
+/// Python files. Only imports that resolve to project files are edges.
+#[derive(Debug, Default)]
+pub struct Graph {
+    /// File -> files that import it.
+    importers: HashMap<String, Vec<String>>,
+}
+
+/// A file reached from a changed file through imports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reached {
+    pub file: String,
+    /// The changed file first, then each importer up to `file`.
+    pub chain: Vec<String>,
+}
+
+impl Graph {
+    /// Read and parse every source file of `repo_files`, relative to `root`.
+    /// Files that resolve outside `root` are left out.
+    pub fn build(root: &Path, repo_files: &[String]) -> Self {
+        let known: HashSet<&str> = repo_files.iter().map(String::as_str).collect();
+        let mut graph = Self::default();
+        for file in repo_files {
+            if language(file).is_none() {
+                continue;
+            }
+            let Ok(text) = super::file_content::read_to_string(root, file) else {
+                continue;
+            };
+            for target in imports(file, &text, &known) {
+                graph.add(file, &target);
+            }
+        }
+        graph
+    }
+
+    fn add(&mut self, importer: &str, imported: &str) {
+        if importer == imported {
+            return;
+        }
+        let entry = self.importers.entry(imported.to_string()).or_default();
+        if !entry.iter().any(|f| f == importer) {
+            entry.push(importer.to_string());
+        }
+    }
+
+    /// Files that import one of `changed`, directly or through up to
+    /// `max_hops` imports, each with the shortest chain that reaches it.
+    /// The changed files themselves are not included.
+    pub fn reach(&self, changed: &[String], max_hops: usize) -> Vec<Reached> {
+        let mut chains: HashMap<&str, Vec<String>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for file in changed {
+            if !chains.contains_key(file.as_str()) {
+                chains.insert(file, vec![file.clone()]);
+                queue.push_back(file.as_str());
+            }
+        }
+        let mut reached = Vec::new();
+        while let Some(file) = queue.pop_front() {
+            let chain = chains[file].clone();
+            if chain.len() > max_hops {
+                continue;
+            }
+            let mut importers: Vec<&String> = self.importers.get(file).into_iter().flatten().collect();
+            importers.sort();
+            for importer in importers {
+                if chains.contains_key(importer.as_str()) {
+                    continue;
+                }
+                let mut next = chain.clone();
+                next.push(importer.clone());
+                reached.push(Reached { file: importer.clone(), chain: next.clone() });
+                chains.insert(importer, next);
+                queue.push_back(importer);
+            }
+        }
+        reached
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Language {
+    Rust,
+    JavaScript,
+    Python,
+}
+
+fn language(file: &str) -> Option<Language> {
+    match Path::new(file).extension()?.to_str()? {
+        "rs" => Some(Language::Rust),
+        "py" => Some(Language::Python),
+        ext if JS_EXTENSIONS.contains(&ext) => Some(Language::JavaScript),
+        _ => None,
+    }
+}
+
+/// Project files imported by `file`, whose contents are `text`.
+fn imports(file: &str, text: &str, known: &HashSet<&str>) -> Vec<String> {
+    let mut found = match language(file) {
+        Some(Language::Rust) => rust_imports(file, text, known),
+        Some(Language::JavaScript) => js_imports(file, text, known),
+        Some(Language::Python) => python_imports(file, text, known),
+        None => Vec::new(),
+    };
+    found.sort();
+    found.dedup();
+    found
+}
+
+fn parent(path: &str) -> &str {
+    path.rfind('/').map_or("", |i| &path[..i])
+}
+
+fn join(dir: &str, rest: &str) -> String {
+    if dir.is_empty() { rest.to_string() } else { format!("{dir}/{rest}") }
+}
+
+/// `dir/rest` with `.` and `..` segments resolved, or None if it climbs above
+/// the project root.
+fn normalize(dir: &str, rest: &str) -> Option<String> {
+    let path = join(dir, rest);
+    let mut parts: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            s => parts.push(s),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// Directory holding the submodules of a Rust source file: its own directory
+/// for `main.rs`, `lib.rs` and `mod.rs`, otherwise a directory named after it.
+fn rust_module_dir(file: &str) -> String {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    match name {
+        "main.rs" | "lib.rs" | "mod.rs" => parent(file).to_string(),
+        _ => file.trim_end_matches(".rs").to_string(),
+    }
+}
+
+/// The module file for `segments` under `dir`, trying the longest prefix
+/// first so `crate::a::b::Item` resolves to `a/b.rs`.
+fn rust_module_file(dir: &str, segments: &[&str], known: &HashSet<&str>) -> Option<String> {
+    (1..=segments.len()).rev().find_map(|n| {
+        let base = join(dir, &segments[..n].join("/"));
+        [format!("{base}.rs"), format!("{base}/mod.rs")]
+            .into_iter()
+            .find(|candidate| known.contains(candidate.as_str()))
+    })
+}
+
+fn rust_imports(file: &str, text: &str, known: &HashSet<&str>) -> Vec<String> {
+    let module_dir = rust_module_dir(file);
+    let mut found = Vec::new();
+    for caps in RUST_MOD_RE.captures_iter(text) {
+        found.extend(rust_module_file(&module_dir, &[&caps[1]], known));
+    }
+    for caps in RUST_USE_RE.captures_iter(text) {
+        for path in expand_use_tree(&caps[1]) {
+            found.extend(resolve_rust_path(file, &module_dir, &path, known));
+        }
+    }
+    found
+}
+
+/// Flatten a `use` tree: `a::{b, c::{d, e as f}}` gives `a::b`, `a::c::d`
+/// and `a::c::e`.
+fn expand_use_tree(tree: &str) -> Vec<String> {
+    let tree = tree.trim();
+    let Some(open) = tree.find('{') else {
+        return vec![tree.split_whitespace().next().unwrap_or_default().to_string()];
+    };
+    let prefix = &tree[..open];
+    let inner = &tree[open + 1..tree.rfind('}').unwrap_or(tree.len())];
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&inner[start..]);
+    parts
+        .into_iter()
+        .filter(|p| !p.trim().is_empty())
+        .flat_map(expand_use_tree)
+        .map(|p| format!("{prefix}{p}"))
+        .collect()
+}
+
+fn resolve_rust_path(file: &str, module_dir: &str, path: &str, known: &HashSet<&str>) -> Option<String> {
+    let mut segments: Vec<&str> = path.split("::").filter(|s| !s.is_empty() && *s != "self" && *s != "*").collect();
+    let mut dir = match path.split("::").next()? {
+        "crate" => {
+            segments.remove(0);
+            rust_crate_root(file, known)?
+        }
+        "super" => {
+            let mut dir = module_dir.to_string();
+            while segments.first() == Some(&"super") {
+                segments.remove(0);
+                dir = parent(&dir).to_string();
+            }
+            dir
+        }
+        "self" => module_dir.to_string(),
+        // Other crates, or a child module named without a prefix.
+        _ => module_dir.to_string(),
+    };
+    if segments.is_empty() {
+        return None;
+    }
+    dir = dir.trim_end_matches('/').to_string();
+    rust_module_file(&dir, &segments, known)
+}
+
+/// The nearest directory above `file` with a `lib.rs` or `main.rs`.
+fn rust_crate_root(file: &str, known: &HashSet<&str>) -> Option<String> {
+    let mut dir = parent(file);
+    loop {
+        if ["lib.rs", "main.rs"].iter().any(|root| known.contains(join(dir, root).as_str())) {
+            return Some(dir.to_string());
+        }
+        if dir.is_empty() {
+            return None;
+        }
+        dir = parent(dir);
+    }
+}
+
+fn js_imports(file: &str, text: &str, known: &HashSet<&str>) -> Vec<String> {
+    let dir = parent(file);
+    JS_IMPORT_RE
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let base = normalize(dir, &caps[1])?;
+            let mut candidates = vec![base.clone()];
+            // TypeScript sources import each other with the compiled `.js` name.
+            if let Some(stem) = base.strip_suffix(".js") {
+                candidates.extend(["ts", "tsx"].iter().map(|ext| format!("{stem}.{ext}")));
+            }
+            candidates.extend(JS_EXTENSIONS.iter().map(|ext| format!("{base}.{ext}")));
+            candidates.extend(JS_EXTENSIONS.iter().map(|ext| format!("{base}/index.{ext}")));
+            candidates.into_iter().find(|c| known.contains(c.as_str()))
+        })
+        .collect()
+}
+
+fn python_imports(file: &str, text: &str, known: &HashSet<&str>) -> Vec<String> {
+    let mut found = Vec::new();
+    for caps in PY_FROM_RE.captures_iter(text) {
+        let dots = caps[1].len();
+        let module = caps[2].replace('.', "/");
+        let names: Vec<&str> = caps[3].split(',').filter_map(|n| n.split_whitespace().next()).collect();
+        if dots > 0 {
+            let mut dir = parent(file);
+            for _ in 1..dots {
+                dir = parent(dir);
+            }
+            let base = join(dir, &module);
+            let base = base.trim_end_matches('/');
+            for name in &names {
+                found.extend(python_module(&join(base, name), known, false));
+            }
+            if !module.is_empty() {
+                found.extend(python_module(base, known, false));
+            }
+        } else {
+            for name in &names {
+                found.extend(python_module(&format!("{module}/{name}"), known, true));
+            }
+            found.extend(python_module(&module, known, true));
+        }
+    }
+    for caps in PY_IMPORT_RE.captures_iter(text) {
+        // The module is the first word of each entry; `as` and its alias follow.
+        for module in caps[1].split(',').filter_map(|m| m.split_whitespace().next()) {
+            found.extend(python_module(&module.replace('.', "/"), known, true));
+        }
+    }
+    found
+}
+
+/// Files for the Python module at `path`. Absolute imports may live under a
+/// source root such as `src/`, so they match any file ending in the path.
+fn python_module(path: &str, known: &HashSet<&str>, absolute: bool) -> Vec<String> {
+    let candidates = [format!("{path}.py"), format!("{path}/__init__.py")];
+    if !absolute {
+        return candidates.into_iter().filter(|c| known.contains(c.as_str())).collect();
+    }
+    known
+        .iter()
+        .filter(|f| candidates.iter().any(|c| *f == c || f.ends_with(&format!("/{c}"))))
+        .map(|f| f.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known(files: &[&'static str]) -> HashSet<&'static str> {
+        files.iter().copied().collect()
+    }
+
+    #[test]
+    fn resolves_rust_mod_and_use() {
+        let files = known(&["src/main.rs", "src/tools/mod.rs", "src/tools/loader.rs", "src/tools/git.rs", "src/model.rs"]);
+        assert_eq!(imports("src/main.rs", "mod tools;\nuse std::fs;\n", &files), vec!["src/tools/mod.rs"]);
+        let text = "use super::git;\nuse crate::model::{Contract, Rule};\nuse super::{\n    loader::{self, load},\n};\n";
+        assert_eq!(
+            imports("src/tools/gate.rs", text, &files),
+            vec!["src/model.rs", "src/tools/git.rs", "src/tools/loader.rs"]
+        );
+    }
+
+    #[test]
+    fn resolves_relative_js_imports() {
+        let files = known(&["src/ingestion/handler.ts", "src/util/parse.ts", "src/util/index.js", "src/db.ts"]);
+        let text = "import { parse } from '../util/parse';\nconst u = require(\"../util\");\nimport db from '../db.js';\nimport x from 'lodash';\n";
+        assert_eq!(
+            imports("src/ingestion/handler.ts", text, &files),
+            vec!["src/db.ts", "src/util/index.js", "src/util/parse.ts"]
+        );
+    }
+
+    #[test]
+    fn resolves_python_imports() {
+        let files = known(&["src/app/api.py", "src/app/models/__init__.py", "src/app/models/user.py", "src/app/util.py"]);
+        let text = "import os\nfrom app.models import user\nfrom .util import helper\n";
+        assert_eq!(
+            imports("src/app/api.py", text, &files),
+            vec!["src/app/models/__init__.py", "src/app/models/user.py", "src/app/util.py"]
+        );
+    }
+
+    #[test]
+    fn resolves_python_imports_with_aliases_and_comments() {
+        let files = known(&["src/app/api.py", "src/app/models/user.py", "src/app/util.py"]);
+        assert_eq!(imports("src/app/api.py", "import app.util  # noqa\n", &files), vec!["src/app/util.py"]);
+        assert_eq!(imports("src/app/api.py", "import app.models.user as user\n", &files), vec!["src/app/models/user.py"]);
+        let text = "import os, app.util as u  # noqa: F401\n";
+        assert_eq!(imports("src/app/api.py", text, &files), vec!["src/app/util.py"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn builds_only_from_files_inside_the_root() {
+        let base = std::env::temp_dir().join("sigil_imports_test_root");
+        let _ = std::fs::remove_dir_all(&base);
+        let (root, outside) = (base.join("project"), base.join("outside"));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "mod a;\n").unwrap();
+        std::fs::write(root.join("src/a.rs"), "").unwrap();
+        std::fs::write(outside.join("b.rs"), "use crate::a;\n").unwrap();
+        std::os::unix::fs::symlink(outside.join("b.rs"), root.join("src/b.rs")).unwrap();
+
+        let files: Vec<String> = ["src/lib.rs", "src/a.rs", "src/b.rs"].map(String::from).into();
+        let reached = Graph::build(&root, &files).reach(&["src/a.rs".to_string()], 1);
+        let reached: Vec<&str> = reached.iter().map(|r| r.file.as_str()).collect();
+        assert_eq!(reached, ["src/lib.rs"], "The symlink leaving the project is not read");
+    }
+
+    #[test]
+    fn reaches_importers_within_max_hops() {
+        let mut graph = Graph::default();
+        graph.add("b.rs", "a.rs");
+        graph.add("c.rs", "b.rs");
+        graph.add("d.rs", "c.rs");
+        let changed = vec!["a.rs".to_string()];
+        let reached = graph.reach(&changed, 2);
+        assert_eq!(reached.len(), 2);
+        assert_eq!(reached[1].file, "c.rs");
+        assert_eq!(reached[1].chain, vec!["a.rs", "b.rs", "c.rs"]);
+        assert_eq!(graph.reach(&changed, 3).len(), 3);
+    }
+}
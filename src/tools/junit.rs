@@ -0,0 +1,195 @@
+use super::report::{self, ContractRow};
+use super::reviews::{ReviewSession, Verdict};
+use super::validation::{Issue, Report};
+use crate::model::Contract;
+use std::fmt::Write;
+
+/// Render a validation run, and optionally a review session, as JUnit XML.
+/// Each contract is a testcase; every validation error and every non-passing
+/// verdict is a failure. Warnings go to the testcase's system-out.
+pub fn render(contracts: &[Contract], validation: &Report, session: Option<&ReviewSession>) -> String {
+    let rows = report::rows(contracts, validation, session);
+    let mut suites = String::new();
+    let mut total_tests = 0;
+    let mut total_failures = 0;
+
+    let mut cases = Vec::new();
+    for row in &rows {
+        let failures = row.errors.iter().map(|i| issue_failure(i)).collect();
+        cases.push(testcase(row, failures, &row.warnings));
+    }
+    let stray = report::unattributed(contracts, &validation.errors);
+    if !stray.is_empty() {
+        let failures = stray.iter().map(|i| issue_failure(i)).collect();
+        cases.push(Case { classname: "sigil".to_string(), name: "contracts".to_string(), failures, out: vec![] });
+    }
+    write_suite(&mut suites, "sigil.validation", &cases, &mut total_tests, &mut total_failures);
+
+    if let Some(s) = session {
+        let cases: Vec<Case> = rows
+            .iter()
+            .filter(|r| !r.verdicts.is_empty())
+            .map(|row| {
+                let failures = row
+                    .verdicts
+                    .iter()
+                    .filter(|v| v.verdict != Verdict::Pass)
+                    .map(|v| {
+                        let target = match &v.rule_id {
+                            Some(rule) => format!("{}/{rule}", v.contract_id),
+                            None => v.contract_id.clone(),
+                        };
+                        Failure {
+                            kind: super::label(&v.verdict),
+                            message: format!("{target}: {}", v.rationale),
+                        }
+                    })
+                    .collect();
+                testcase(row, failures, &[])
+            })
+            .collect();
+        let name = format!("sigil.review.{}", s.review_id);
+        write_suite(&mut suites, &name, &cases, &mut total_tests, &mut total_failures);
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"sigil\" tests=\"{total_tests}\" failures=\"{total_failures}\">\n{suites}</testsuites>\n"
+    )
+}
+
+struct Failure {
+    kind: String,
+    message: String,
+}
+
+struct Case {
+    classname: String,
+    name: String,
+    failures: Vec<Failure>,
+    out: Vec<String>,
+}
+
+fn issue_failure(issue: &Issue) -> Failure {
+    Failure { kind: issue.kind.to_string(), message: issue.message.clone() }
+}
+
+fn testcase(row: &ContractRow, failures: Vec<Failure>, warnings: &[&Issue]) -> Case {
+    Case {
+        classname: format!("sigil.{}", super::label(&row.contract.priority)),
+        name: row.contract.id.clone(),
+        failures,
+        out: warnings.iter().map(|w| format!("warning {}: {}", w.kind, w.message)).collect(),
+    }
+}
+
+fn write_suite(out: &mut String, name: &str, cases: &[Case], tests: &mut usize, failures: &mut usize) {
+    let failed = cases.iter().filter(|c| !c.failures.is_empty()).count();
+    *tests += cases.len();
+    *failures += failed;
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failed}\">",
+        escape(name),
+        cases.len()
+    );
+    for case in cases {
+        let open = format!(
+            "    <testcase classname=\"{}\" name=\"{}\"",
+            escape(&case.classname),
+            escape(&case.name)
+        );
+        if case.failures.is_empty() && case.out.is_empty() {
+            let _ = writeln!(out, "{open}/>");
+            continue;
+        }
+        let _ = writeln!(out, "{open}>");
+        for f in &case.failures {
+            let _ = writeln!(
+                out,
+                "      <failure type=\"{}\" message=\"{}\"/>",
+                escape(&f.kind),
+                escape(&f.message)
+            );
+        }
+        if !case.out.is_empty() {
+            let _ = writeln!(out, "      <system-out>{}</system-out>", escape(&case.out.join("\n")));
+        }
+        let _ = writeln!(out, "    </testcase>");
+    }
+    let _ = writeln!(out, "  </testsuite>");
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::reviews::VerdictRecord;
+
+    fn contract(id: &str) -> Contract {
+        toml::from_str(&format!(
+            "id = \"{id}\"\nversion = \"1.0.0\"\nname = \"{id}\"\ndescription = \"d\"\npriority = \"must\"\n"
+        ))
+        .unwrap()
+    }
+
+    fn error(contract_id: Option<&str>, message: &str) -> Issue {
+        Issue {
+            kind: "missing_file",
+            contract_id: contract_id.map(str::to_string),
+            message: message.to_string(),
+            file: None,
+            source: None,
+            span: None,
+        }
+    }
+
+    #[test]
+    fn one_testcase_per_contract_with_failures_per_issue() {
+        let contracts = vec![contract("a"), contract("b")];
+        let report = Report {
+            errors: vec![error(Some("a"), "gone <a.rs>"), error(Some("a"), "gone too"), error(None, "bad toml")],
+            warnings: vec![],
+            ..Report::default()
+        };
+        let xml = render(&contracts, &report, None);
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<testsuite name=\"sigil.validation\" tests=\"3\" failures=\"2\">"));
+        assert!(xml.contains("<testcase classname=\"sigil.must\" name=\"b\"/>"));
+        assert_eq!(xml.matches("<failure type=\"missing_file\"").count(), 3);
+        assert!(xml.contains("message=\"gone &lt;a.rs&gt;\""));
+    }
+
+    #[test]
+    fn review_suite_fails_non_passing_verdicts() {
+        let contracts = vec![contract("a")];
+        let session = ReviewSession {
+            review_id: "pr-1".to_string(),
+            verdicts: vec![VerdictRecord {
+                contract_id: "a".to_string(),
+                rule_id: Some("r1".to_string()),
+                verdict: Verdict::NeedsHumanReview,
+                rationale: "unclear".to_string(),
+                evidence: vec![],
+                recorded_at: String::new(),
+            }],
+        };
+        let xml = render(&contracts, &Report::default(), Some(&session));
+        assert!(xml.contains("<testsuite name=\"sigil.review.pr-1\" tests=\"1\" failures=\"1\">"));
+        assert!(xml.contains("type=\"needs_human_review\" message=\"a/r1: unclear\""));
+        assert!(xml.contains("<testsuites name=\"sigil\" tests=\"2\" failures=\"1\">"));
+    }
+}
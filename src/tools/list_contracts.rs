@@ -1,6 +1,7 @@
 use crate::model::{Contract, Priority, Status};
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct Params {
@@ -8,6 +9,9 @@ pub struct Params {
     pub domain: Option<String>,
     /// Filter by tags (OR logic: contracts matching any provided tag are returned).
     pub tags: Option<Vec<String>>,
+    /// Filter by trigger fields (AND logic: every given field must have an equal value),
+    /// e.g. {"type": "kafka-message", "topic": "device-data"}. The key "type" matches the trigger type.
+    pub trigger: Option<BTreeMap<String, serde_json::Value>>,
 }
 
 #[derive(Serialize)]
@@ -49,6 +53,11 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
                     return false;
                 }
             }
+            if let Some(filter) = &params.trigger
+                && !super::triggers::matches(c, filter)
+            {
+                return false;
+            }
             true
         })
         .collect();
@@ -98,8 +107,7 @@ mod tests {
     fn make_server(contracts_dir: &str) -> super::super::SigilServer {
         super::super::SigilServer::new(Config {
             contracts_dir: contracts_dir.to_string(),
-            instructions: None,
-            notes: None,
+            ..Config::default()
         })
     }
 
@@ -136,7 +144,7 @@ mod tests {
         write_contract(&dir, "contract-a", Some("core"), &["tag1"]);
         write_contract(&dir, "contract-b", Some("tools"), &["tag2"]);
         let server = make_server(dir.to_str().unwrap());
-        let result = handle(&server, Params { domain: None, tags: None }).await;
+        let result = handle(&server, Params { domain: None, tags: None, trigger: None }).await;
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(json["total"], 2);
     }
@@ -152,6 +160,7 @@ mod tests {
             Params {
                 domain: Some("core".to_string()),
                 tags: None,
+                trigger: None,
             },
         )
         .await;
@@ -170,6 +179,7 @@ mod tests {
             Params {
                 domain: Some("core".to_string()),
                 tags: None,
+                trigger: None,
             },
         )
         .await;
@@ -189,6 +199,7 @@ mod tests {
             Params {
                 domain: None,
                 tags: Some(vec!["alpha".to_string(), "gamma".to_string()]),
+                trigger: None,
             },
         )
         .await;
@@ -207,6 +218,7 @@ mod tests {
             Params {
                 domain: Some("core".to_string()),
                 tags: Some(vec!["mcp".to_string()]),
+                trigger: None,
             },
         )
         .await;
@@ -214,4 +226,29 @@ mod tests {
         assert_eq!(json["total"], 1, "Combined filters require AND logic");
         assert_eq!(json["contracts"][0]["id"], "contract-a");
     }
+
+    #[tokio::test]
+    async fn filters_by_trigger_fields() {
+        let dir = temp_dir("trigger");
+        write_contract(&dir, "contract-a", None, &[]);
+        let with_trigger = |id: &str, topic: &str| {
+            format!("id = \"{id}\"\nversion = \"1.0.0\"\nname = \"{id}\"\ndescription = \"desc\"\n\n[trigger]\ntype = \"kafka-message\"\ntopic = \"{topic}\"\n")
+        };
+        fs::write(dir.join("contract-b.contract.toml"), with_trigger("contract-b", "device-data")).unwrap();
+        fs::write(dir.join("contract-c.contract.toml"), with_trigger("contract-c", "billing")).unwrap();
+        let server = make_server(dir.to_str().unwrap());
+        let filter = serde_json::json!({"type": "kafka-message", "topic": "device-data"});
+        let result = handle(
+            &server,
+            Params {
+                domain: None,
+                tags: None,
+                trigger: Some(serde_json::from_value(filter).unwrap()),
+            },
+        )
+        .await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["total"], 1, "Every trigger field must match");
+        assert_eq!(json["contracts"][0]["id"], "contract-b");
+    }
 }
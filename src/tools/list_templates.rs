@@ -0,0 +1,78 @@
+use super::templates::Variable;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {}
+
+#[derive(Serialize)]
+struct Response {
+    templates: Vec<Summary>,
+    total: usize,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Summary {
+    name: String,
+    path: String,
+    description: Option<String>,
+    variables: Vec<Variable>,
+}
+
+pub async fn handle(server: &super::SigilServer, _params: Params) -> String {
+    let (templates, warnings) = super::templates::load_templates(&server.config.contracts_dir);
+    let templates: Vec<Summary> = templates
+        .into_iter()
+        .map(|t| Summary {
+            name: t.name,
+            path: t.path,
+            description: t.description,
+            variables: t.variables,
+        })
+        .collect();
+    let total = templates.len();
+    serde_json::to_string(&Response { templates, total, warnings }).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::tools::SigilServer;
+    use std::fs;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_list_templates_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn lists_templates_with_their_variables() {
+        let dir = temp_dir("basic");
+        let server = SigilServer::new(Config {
+            contracts_dir: dir.to_str().unwrap().to_string(),
+            ..Config::default()
+        });
+        let empty: serde_json::Value = serde_json::from_str(&handle(&server, Params {}).await).unwrap();
+        assert_eq!(empty["total"], 0, "A missing templates dir means no templates");
+
+        fs::create_dir_all(dir.join("templates")).unwrap();
+        fs::write(
+            dir.join("templates/http-endpoint.template.toml"),
+            "id = \"{{id}}\"\nname = \"{{method}} {{path}}\"\n\n[template.variables]\npath = \"Route path\"\n",
+        )
+        .unwrap();
+        let response: serde_json::Value = serde_json::from_str(&handle(&server, Params {}).await).unwrap();
+        assert_eq!(response["total"], 1);
+        let template = &response["templates"][0];
+        assert_eq!(template["name"], "http-endpoint");
+        assert!(template["description"].is_null());
+        assert_eq!(
+            template["variables"],
+            serde_json::json!([{"name": "path", "description": "Route path"}, {"name": "id"}, {"name": "method"}])
+        );
+    }
+}
@@ -1,14 +1,101 @@
-use crate::model::Contract;
+use super::locate::Span;
+use crate::model::{Contract, Waiver, WaiversFile};
+use std::path::Path;
 use walkdir::WalkDir;
 use toml;
 
+/// Subdirectory of the contracts dir holding archived contracts. Archived
+/// contracts are kept for history but never loaded.
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// Subdirectory of the contracts dir holding `*.template.toml` contract
+/// templates, rendered by `sigil_create_contract`.
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// File in the contracts dir listing known, time-boxed contract violations.
+pub const WAIVERS_FILE: &str = "waivers.toml";
+
+/// True if `id` is kebab-case: lowercase ASCII letters and digits in
+/// non-empty groups separated by single hyphens.
+pub fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()))
+}
+
+/// Path of the contract file for `id` directly inside `dir`. Ids that are not
+/// kebab-case are rejected, so a caller-supplied id can never name a file
+/// outside `dir`.
+pub fn contract_path(dir: &str, id: &str) -> Result<String, String> {
+    if !is_valid_id(id) {
+        return Err(format!("Invalid contract id '{id}': ids must be kebab-case (e.g. 'no-pii-in-logs')"));
+    }
+    Ok(format!("{}/{id}.contract.toml", dir.trim_end_matches('/')))
+}
+
+/// True if `path` lies inside the archive subdirectory of `dir`.
+pub fn is_archived(dir: &str, path: &Path) -> bool {
+    path.starts_with(Path::new(dir).join(ARCHIVE_DIR))
+}
+
+/// A file in the contracts dir that could not be read or parsed.
+#[derive(Debug, Clone)]
+pub struct LoadError {
+    pub path: String,
+    pub message: String,
+    /// Location of a TOML syntax or type error, when the parser reports one.
+    pub span: Option<Span>,
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Load all contracts. The warnings cover files that failed to load,
+/// contract ids declared by more than one file, and stray contract-like files.
 pub fn load_contracts(dir: &str) -> (Vec<Contract>, Vec<String>) {
+    let (contracts, errors) = load_contracts_detailed(dir);
+    let mut warnings: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    for (id, paths) in duplicate_ids(&contracts) {
+        warnings.push(format!("Contract id '{id}' is declared by more than one file: {}", paths.join(", ")));
+    }
+    for path in stray_contract_files(dir) {
+        warnings.push(format!(
+            "{path} looks like a contract but is not loaded: contract files must end in .contract.toml"
+        ));
+    }
+    (contracts, warnings)
+}
+
+/// Like [`load_contracts`], but with the location of each parse failure.
+pub fn load_contracts_detailed(dir: &str) -> (Vec<Contract>, Vec<LoadError>) {
     let mut contracts = Vec::new();
-    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+
+    for path in contract_files(dir) {
+        match parse_file::<Contract>(&path) {
+            Ok(Some(mut contract)) => {
+                contract.source = Some(path);
+                contracts.push(contract);
+            }
+            Ok(None) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+
+    contracts.sort_by(|a, b| a.id.cmp(&b.id).then_with(|| a.source.cmp(&b.source)));
+    (contracts, errors)
+}
 
-    for entry in WalkDir::new(dir)
+/// Paths of the `*.contract.toml` files under `dir`, archived ones excluded.
+pub fn contract_files(dir: &str) -> Vec<String> {
+    WalkDir::new(dir)
         .follow_links(true)
         .into_iter()
+        .filter_entry(|e| !is_archived(dir, e.path()))
         .filter_map(|e| e.ok())
         .filter(|e| {
             e.file_type().is_file()
@@ -16,19 +103,105 @@ pub fn load_contracts(dir: &str) -> (Vec<Contract>, Vec<String>) {
                     .to_string_lossy()
                     .ends_with(".contract.toml")
         })
-    {
-        let path = entry.path().display().to_string();
-        match std::fs::read_to_string(entry.path()) {
-            Ok(content) => match toml::from_str::<Contract>(&content) {
-                Ok(contract) => contracts.push(contract),
-                Err(e) => warnings.push(format!("Failed to parse {path}: {e}")),
-            },
-            Err(e) => warnings.push(format!("Failed to read {path}: {e}")),
+        .map(|e| e.path().display().to_string())
+        .collect()
+}
+
+/// Ids declared by more than one contract file, with the files declaring each.
+pub fn duplicate_ids(contracts: &[Contract]) -> Vec<(String, Vec<String>)> {
+    let mut duplicates: Vec<(String, Vec<String>)> = Vec::new();
+    for pair in contracts.windows(2) {
+        if pair[0].id != pair[1].id {
+            continue;
+        }
+        let path = pair[1].source.clone().unwrap_or_default();
+        match duplicates.last_mut() {
+            Some((id, paths)) if *id == pair[0].id => paths.push(path),
+            _ => duplicates.push((pair[0].id.clone(), vec![pair[0].source.clone().unwrap_or_default(), path])),
         }
     }
+    duplicates
+}
 
-    contracts.sort_by(|a, b| a.id.cmp(&b.id));
-    (contracts, warnings)
+/// Find the contract declaring `id`. Refuses to pick one when several files
+/// declare the same id.
+pub fn find_unique<'a>(contracts: &'a [Contract], id: &str) -> Result<Option<&'a Contract>, String> {
+    let matches: Vec<&Contract> = contracts.iter().filter(|c| c.id == id).collect();
+    if matches.len() > 1 {
+        let paths: Vec<&str> = matches.iter().filter_map(|c| c.source.as_deref()).collect();
+        return Err(format!(
+            "Contract id '{id}' is declared by more than one file ({}). Remove or rename the duplicates first.",
+            paths.join(", ")
+        ));
+    }
+    Ok(matches.first().copied())
+}
+
+/// `*.toml` files under `dir` that look like contracts (a string `id` plus a
+/// `version`, `name`, `description` or `rules` key) but lack the
+/// `.contract.toml` suffix, so the loader never sees them. The waivers file,
+/// templates and archived files are not considered.
+pub fn stray_contract_files(dir: &str) -> Vec<String> {
+    let waivers = Path::new(dir).join(WAIVERS_FILE);
+    let templates = Path::new(dir).join(TEMPLATES_DIR);
+    let mut stray: Vec<String> = WalkDir::new(dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|e| !is_archived(dir, e.path()) && !e.path().starts_with(&templates))
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.file_type().is_file()
+                && name.ends_with(".toml")
+                && !name.ends_with(".contract.toml")
+                && e.path() != waivers
+        })
+        .filter(|e| {
+            let Ok(content) = std::fs::read_to_string(e.path()) else {
+                return false;
+            };
+            let Ok(table) = content.parse::<toml::Table>() else {
+                return false;
+            };
+            table.get("id").is_some_and(toml::Value::is_str)
+                && ["version", "name", "description", "rules"]
+                    .iter()
+                    .any(|key| table.contains_key(*key))
+        })
+        .map(|e| e.path().display().to_string())
+        .collect();
+    stray.sort();
+    stray
+}
+
+/// Load the waivers file. A missing file means no waivers; a malformed file
+/// yields no waivers and an error.
+pub fn load_waivers(dir: &str) -> (Vec<Waiver>, Option<LoadError>) {
+    let path = Path::new(dir).join(WAIVERS_FILE).display().to_string();
+    match parse_file::<WaiversFile>(&path) {
+        Ok(file) => (file.map(|f| f.waivers).unwrap_or_default(), None),
+        Err(e) => (vec![], Some(e)),
+    }
+}
+
+/// Read and parse a TOML file. Returns `Ok(None)` if it does not exist.
+fn parse_file<T: serde::de::DeserializeOwned>(path: &str) -> Result<Option<T>, LoadError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(LoadError {
+                path: path.to_string(),
+                message: format!("Failed to read {path}: {e}"),
+                span: None,
+            });
+        }
+    };
+    toml::from_str(&content).map(Some).map_err(|e| LoadError {
+        path: path.to_string(),
+        message: format!("Failed to parse {path}: {e}"),
+        span: e.span().map(|range| Span::from_range(&content, range)),
+    })
 }
 
 #[cfg(test)]
@@ -81,6 +254,16 @@ description = "A test contract"
         assert!(!warnings.is_empty(), "Must warn on parse failure");
     }
 
+    #[test]
+    fn parse_errors_carry_a_source_span() {
+        let dir = temp_dir("span");
+        write(&dir, "bad.contract.toml", "id = \"bad\"\nversion = 1\n");
+        let (_, errors) = load_contracts_detailed(dir.to_str().unwrap());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].path.ends_with("bad.contract.toml"));
+        assert_eq!(errors[0].span.map(|s| s.line), Some(2));
+    }
+
     #[test]
     fn warns_on_missing_required_fields() {
         let dir = temp_dir("missing_fields");
@@ -100,6 +283,80 @@ description = "A test contract"
         assert!(warnings.is_empty());
     }
 
+    #[test]
+    fn skips_archived_contracts() {
+        let dir = temp_dir("archived");
+        fs::create_dir_all(dir.join(ARCHIVE_DIR)).unwrap();
+        write(&dir.join(ARCHIVE_DIR), "my-contract.contract.toml", VALID);
+        let (contracts, warnings) = load_contracts(dir.to_str().unwrap());
+        assert!(contracts.is_empty(), "Archived contracts must not be loaded");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn loads_waivers_file() {
+        let dir = temp_dir("waivers");
+        write(&dir, WAIVERS_FILE, r#"
+[[waivers]]
+contract_id = "my-contract"
+paths = ["src/legacy/**"]
+reason = "legacy module"
+owner = "platform-team"
+expires = "2030-01-31"
+"#);
+        let (waivers, error) = load_waivers(dir.to_str().unwrap());
+        assert!(error.is_none(), "{error:?}");
+        assert_eq!(waivers.len(), 1);
+        assert_eq!(waivers[0].owner, "platform-team");
+    }
+
+    #[test]
+    fn missing_waivers_file_is_not_an_error() {
+        let dir = temp_dir("no_waivers");
+        let (waivers, error) = load_waivers(dir.to_str().unwrap());
+        assert!(waivers.is_empty());
+        assert!(error.is_none());
+    }
+
+    #[test]
+    fn reports_duplicate_ids_across_subdirectories() {
+        let dir = temp_dir("duplicates");
+        write(&dir, "my-contract.contract.toml", VALID);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        write(&dir.join("nested"), "copy.contract.toml", VALID);
+        let (contracts, warnings) = load_contracts(dir.to_str().unwrap());
+        assert_eq!(contracts.len(), 2);
+        let duplicates = duplicate_ids(&contracts);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].1.len(), 2);
+        assert!(warnings[0].contains("nested"), "{warnings:?}");
+        let err = find_unique(&contracts, "my-contract").unwrap_err();
+        assert!(err.contains("copy.contract.toml") && err.contains("my-contract.contract.toml"));
+    }
+
+    #[test]
+    fn warns_on_stray_contract_like_files() {
+        let dir = temp_dir("stray");
+        write(&dir, "forgot-suffix.toml", VALID);
+        write(&dir, "config.toml", "[config]\n");
+        write(&dir, WAIVERS_FILE, "id = \"x\"\nname = \"not a contract\"\n");
+        fs::create_dir_all(dir.join(TEMPLATES_DIR)).unwrap();
+        write(&dir.join(TEMPLATES_DIR), "service.template.toml", "id = \"{{id}}\"\nname = \"{{name}}\"\n");
+        let stray = stray_contract_files(dir.to_str().unwrap());
+        assert_eq!(stray.len(), 1);
+        assert!(stray[0].ends_with("forgot-suffix.toml"));
+        let (_, warnings) = load_contracts(dir.to_str().unwrap());
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn contract_paths_stay_inside_the_contracts_dir() {
+        assert_eq!(contract_path("contracts/", "a-b1").unwrap(), "contracts/a-b1.contract.toml");
+        for id in ["../escape", "a/b", "", "A", "a--b", "-a", "a.b"] {
+            assert!(contract_path("contracts", id).is_err(), "{id}");
+        }
+    }
+
     #[test]
     fn sorts_contracts_by_id() {
         let dir = temp_dir("sorted");
@@ -0,0 +1,126 @@
+use serde::Serialize;
+use std::ops::Range;
+use toml_edit::{ImDocument, Item, Table, Value};
+
+/// A region of a text file. Lines and columns are 1-based; columns count
+/// characters, and `end_column` is one past the last character.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl Span {
+    /// Convert a byte range of `text` into a line/column span.
+    pub fn from_range(text: &str, range: Range<usize>) -> Span {
+        let (line, column) = position(text, range.start);
+        let (end_line, end_column) = position(text, range.end.max(range.start));
+        Span { line, column, end_line, end_column }
+    }
+}
+
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// Resolves JSON pointers (as reported by schema validation of a contract
+/// converted to JSON) to spans in the TOML document the contract came from.
+pub struct Locator {
+    doc: ImDocument<String>,
+}
+
+enum Node<'a> {
+    Table(&'a Table),
+    Item(&'a Item),
+    Value(&'a Value),
+}
+
+impl<'a> Node<'a> {
+    /// The child at `segment` and the span to report for it: the key for
+    /// table entries, the element for array entries.
+    fn child(&self, segment: &str) -> Option<(Node<'a>, Option<Range<usize>>)> {
+        let entry = |(key, item): (&'a toml_edit::Key, &'a Item)| {
+            (Node::Item(item), key.span().or_else(|| item.span()))
+        };
+        match self {
+            Node::Table(t) => t.get_key_value(segment).map(entry),
+            Node::Item(Item::Table(t)) => t.get_key_value(segment).map(entry),
+            Node::Item(Item::ArrayOfTables(a)) => {
+                let t = a.get(segment.parse().ok()?)?;
+                Some((Node::Table(t), t.span()))
+            }
+            Node::Item(Item::Value(v)) => Node::Value(v).child(segment),
+            Node::Value(Value::InlineTable(t)) => t.get_key_value(segment).map(entry),
+            Node::Value(Value::Array(a)) => {
+                let v = a.get(segment.parse().ok()?)?;
+                Some((Node::Value(v), v.span()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Locator {
+    pub fn new(text: &str) -> Option<Locator> {
+        ImDocument::parse(text.to_string()).ok().map(|doc| Locator { doc })
+    }
+
+    pub fn from_file(path: &str) -> Option<Locator> {
+        Locator::new(&std::fs::read_to_string(path).ok()?)
+    }
+
+    /// Span of the deepest part of `pointer` present in the document. A
+    /// pointer to the root (or to nothing in the document) yields the start
+    /// of the file.
+    pub fn span(&self, pointer: &str) -> Span {
+        let mut node = Node::Table(self.doc.as_table());
+        let mut range = None;
+        for segment in pointer.split('/').skip(1) {
+            let segment = segment.replace("~1", "/").replace("~0", "~");
+            let Some((child, child_range)) = node.child(&segment) else {
+                break;
+            };
+            node = child;
+            range = child_range.or(range);
+        }
+        Span::from_range(self.doc.raw(), range.unwrap_or(0..0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "id = \"x\"\nfiles = [\"a.rs\", \"b.rs\"]\n\n[[rules]]\nid = \"r1\"\n\n[[rules]]\nid = \"Bad Id\"\n";
+
+    #[test]
+    fn converts_byte_ranges_to_lines_and_columns() {
+        let span = Span::from_range("ab\ncdé\n", 5..7);
+        assert_eq!(span, Span { line: 2, column: 3, end_line: 2, end_column: 4 });
+    }
+
+    #[test]
+    fn resolves_pointers_into_arrays_of_tables_and_arrays() {
+        let locator = Locator::new(DOC).unwrap();
+        let rule_id = locator.span("/rules/1/id");
+        assert_eq!((rule_id.line, rule_id.column, rule_id.end_column), (8, 1, 3));
+        let file = locator.span("/files/1");
+        assert_eq!((file.line, file.column), (2, 18));
+    }
+
+    #[test]
+    fn unknown_pointers_fall_back_to_the_deepest_match() {
+        let locator = Locator::new(DOC).unwrap();
+        assert_eq!(locator.span("/rules/1/missing").line, 7);
+        assert_eq!(locator.span("").line, 1);
+    }
+}
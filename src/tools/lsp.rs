@@ -0,0 +1,670 @@
+//! A language server for editing `.contract.toml` files, spoken over stdio.
+//! Diagnostics come from the same validation as `sigil_validate_contract`,
+//! run on the editor buffer rather than the file on disk. Rule checks, which
+//! read project files, run when a document is opened or saved, not on every
+//! change.
+
+use super::schema::SCHEMA;
+use super::validation::{self, Issue};
+use crate::config::Config;
+use crate::model::Contract;
+use globset::Glob;
+use regex::Regex;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::LazyLock;
+
+/// `[table]` and `[[array.of.tables]]` headers.
+static HEADER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*\[\[?\s*([A-Za-z_.]+)\s*\]\]?").unwrap());
+/// A key being typed at the start of a line.
+static KEY_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*[A-Za-z_]*$").unwrap());
+/// A value being typed after `key =`, with or without its opening quote.
+static VALUE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^\s*([A-Za-z_]+)\s*=\s*("?)[\w-]*$"#).unwrap());
+static ASSIGNMENT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"([A-Za-z_]+)\s*=\s*$").unwrap());
+
+/// Keys whose string values are project file paths.
+const PATH_KEYS: &[&str] = &["files", "file", "tests"];
+
+/// Most path completions offered at once.
+const MAX_COMPLETIONS: usize = 500;
+
+// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Serve the language server protocol on stdin/stdout until the client sends
+/// `exit`. Exits with failure if `exit` comes without a prior `shutdown`.
+/// Rule checks read project files under `root`.
+pub fn run(config: Config, root: PathBuf) -> std::io::Result<ExitCode> {
+    let mut server = Server::new(config, root);
+    let mut input = std::io::stdin().lock();
+    let mut output = std::io::stdout().lock();
+    while let Some(message) = read_message(&mut input)? {
+        for reply in server.handle(message) {
+            write_message(&mut output, &reply)?;
+        }
+        if server.exited {
+            break;
+        }
+    }
+    Ok(if server.shutdown { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+/// Read one `Content-Length` framed message. Returns None at end of input,
+/// and a JSON-RPC parse error response for a body that is not JSON.
+fn read_message(input: &mut impl BufRead) -> std::io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut body = vec![0; length.unwrap_or_default()];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body).unwrap_or_else(|e| {
+        json!({ "jsonrpc": "2.0", "id": null, "error": { "code": PARSE_ERROR, "message": e.to_string() } })
+    })))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+struct Server {
+    config: Config,
+    /// Project root that rule checks and the file list are read from.
+    root: PathBuf,
+    /// Open documents by URI, with their current text.
+    documents: HashMap<String, String>,
+    /// Project files, refreshed whenever a document is saved.
+    repo_files: Vec<String>,
+    /// Contracts on disk, refreshed whenever a document is saved.
+    contracts: Vec<Contract>,
+    shutdown: bool,
+    exited: bool,
+}
+
+impl Server {
+    fn new(config: Config, root: PathBuf) -> Self {
+        let (contracts, _) = super::loader::load_contracts(&config.contracts_dir);
+        Self {
+            config,
+            repo_files: super::repo_files::list(&root),
+            root,
+            documents: HashMap::new(),
+            contracts,
+            shutdown: false,
+            exited: false,
+        }
+    }
+
+    /// Handle one incoming message, returning the messages to send back.
+    fn handle(&mut self, message: Value) -> Vec<Value> {
+        // A parse error from read_message goes straight back.
+        if message.get("error").is_some() {
+            return vec![message];
+        }
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+        let result = match method {
+            "initialize" => Some(json!({
+                "capabilities": {
+                    "textDocumentSync": { "openClose": true, "change": 1, "save": { "includeText": false } },
+                    "completionProvider": { "triggerCharacters": ["\"", "/", "=", " "] },
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                },
+                "serverInfo": { "name": "sigil-mcp", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Some(Value::Null)
+            }
+            "exit" => {
+                self.exited = true;
+                return vec![];
+            }
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_string());
+                return self.diagnostics(&uri, true).into_iter().collect();
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole text.
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes.and_then(|c| c.last()).and_then(|c| c["text"].as_str()) {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                return self.diagnostics(&uri, false).into_iter().collect();
+            }
+            "textDocument/didSave" => {
+                // Saving may create or remove referenced files; recheck every open document.
+                self.repo_files = super::repo_files::list(&self.root);
+                self.contracts = super::loader::load_contracts(&self.config.contracts_dir).0;
+                let mut uris: Vec<String> = self.documents.keys().cloned().collect();
+                uris.sort();
+                return uris.iter().filter_map(|u| self.diagnostics(u, true)).collect();
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![publish(&uri, vec![])];
+            }
+            "textDocument/completion" => Some(self.completion(&uri, &params["position"])),
+            "textDocument/hover" => Some(self.hover(&uri, &params["position"])),
+            "textDocument/definition" => Some(self.definition(&uri, &params["position"])),
+            _ => None,
+        };
+        // Notifications (no id) get no reply.
+        let Some(id) = message.get("id").cloned() else {
+            return vec![];
+        };
+        match result {
+            Some(result) => vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })],
+            None => vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": METHOD_NOT_FOUND, "message": format!("Unsupported method '{method}'") },
+            })],
+        }
+    }
+
+    /// A publishDiagnostics notification for an open contract document, with
+    /// the results of its rule checks if `run_rule_checks`.
+    fn diagnostics(&self, uri: &str, run_rule_checks: bool) -> Option<Value> {
+        let text = self.documents.get(uri)?;
+        let path = uri_to_path(uri)?;
+        if !path.ends_with(".contract.toml") {
+            return None;
+        }
+        let (config, root, files, contracts) = (&self.config, &self.root, &self.repo_files, &self.contracts);
+        let report = validation::validate_text(config, root, &path, text, files, contracts, run_rule_checks);
+        let errors = report.errors.iter().map(|issue| diagnostic(text, issue, 1));
+        let warnings = report.warnings.iter().map(|issue| diagnostic(text, issue, 2));
+        Some(publish(uri, errors.chain(warnings).collect()))
+    }
+
+    fn completion(&self, uri: &str, position: &Value) -> Value {
+        let Some((text, offset)) = self.locate(uri, position) else {
+            return json!([]);
+        };
+        let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let prefix = &text[line_start..offset];
+
+        if let Some(partial) = open_string(prefix) {
+            let key = key_at(text, offset);
+            if key.as_deref().is_some_and(|k| PATH_KEYS.contains(&k)) {
+                let start = position_at(text, offset - partial.len());
+                let end = position_at(text, offset);
+                return json!(self.path_completions(partial, start, end));
+            }
+        }
+        if let Some(caps) = VALUE_RE.captures(prefix) {
+            let quoted = !caps[2].is_empty();
+            let items: Vec<Value> = value_choices(&self.schemas(), current_table(text, line_start).as_deref(), &caps[1])
+                .into_iter()
+                .map(|value| {
+                    let insert = if quoted { value.clone() } else { format!("\"{value}\"") };
+                    json!({ "label": value, "kind": 20, "insertText": insert })
+                })
+                .collect();
+            return json!(items);
+        }
+        if KEY_RE.is_match(prefix) {
+            let items: Vec<Value> = field_choices(&self.schemas(), current_table(text, line_start).as_deref())
+                .into_iter()
+                .map(|(name, description)| json!({ "label": name, "kind": 10, "documentation": description }))
+                .collect();
+            return json!(items);
+        }
+        json!([])
+    }
+
+    /// The contract schemas completions draw on: the built-in schema and the
+    /// project's schema extension, if any.
+    fn schemas(&self) -> Vec<&Value> {
+        let extension = self.config.schema.extension_schema.as_ref().map(|e| &e.schema);
+        std::iter::once(&*SCHEMA).chain(extension).collect()
+    }
+
+    /// Completions for a path being typed: the files and directories one
+    /// segment below what has been typed so far.
+    fn path_completions(&self, partial: &str, start: Value, end: Value) -> Vec<Value> {
+        let mut seen = Vec::new();
+        for file in self.repo_files.iter().filter(|f| f.starts_with(partial)) {
+            let rest = &file[partial.len()..];
+            let label = match rest.find('/') {
+                Some(i) => format!("{partial}{}", &rest[..=i]),
+                None => file.clone(),
+            };
+            if !seen.contains(&label) {
+                seen.push(label);
+            }
+        }
+        seen.sort();
+        seen.truncate(MAX_COMPLETIONS);
+        seen.into_iter()
+            .map(|label| {
+                let kind = if label.ends_with('/') { 19 } else { 17 };
+                json!({
+                    "label": label,
+                    "kind": kind,
+                    "textEdit": { "range": { "start": start, "end": end }, "newText": label },
+                })
+            })
+            .collect()
+    }
+
+    /// For an applies_to pattern, the project files it matches.
+    fn hover(&self, uri: &str, position: &Value) -> Value {
+        let Some((text, offset)) = self.locate(uri, position) else {
+            return Value::Null;
+        };
+        let Some((pattern, range)) = string_at(text, offset) else {
+            return Value::Null;
+        };
+        if key_at(text, offset).as_deref() != Some("applies_to") {
+            return Value::Null;
+        }
+        let contents = match Glob::new(&pattern) {
+            Err(e) => format!("Invalid pattern: {e}"),
+            Ok(glob) => {
+                let matcher = glob.compile_matcher();
+                let matched: Vec<&String> = self.repo_files.iter().filter(|f| matcher.is_match(f.as_str())).collect();
+                let mut contents = format!("`{pattern}` matches {} project file(s)", matched.len());
+                for file in matched.iter().take(50) {
+                    contents.push_str(&format!("\n- `{file}`"));
+                }
+                if matched.len() > 50 {
+                    contents.push_str(&format!("\n- and {} more", matched.len() - 50));
+                }
+                contents
+            }
+        };
+        json!({
+            "contents": { "kind": "markdown", "value": contents },
+            "range": { "start": position_at(text, range.start), "end": position_at(text, range.end) },
+        })
+    }
+
+    /// From a file reference (in `files`, a check's `file`, or a `tests`
+    /// entry) to the file itself.
+    fn definition(&self, uri: &str, position: &Value) -> Value {
+        let Some((text, offset)) = self.locate(uri, position) else {
+            return Value::Null;
+        };
+        let Some((value, _)) = string_at(text, offset) else {
+            return Value::Null;
+        };
+        if !key_at(text, offset).as_deref().is_some_and(|k| PATH_KEYS.contains(&k)) {
+            return Value::Null;
+        }
+        let path = value.split("::").next().unwrap_or_default();
+        if super::file_content::check_reference(path).is_err() || !std::path::Path::new(path).is_file() {
+            return Value::Null;
+        }
+        let start = json!({ "line": 0, "character": 0 });
+        json!({ "uri": path_to_uri(path), "range": { "start": start, "end": start } })
+    }
+
+    /// The text of an open document and the byte offset of an LSP position in it.
+    fn locate(&self, uri: &str, position: &Value) -> Option<(&str, usize)> {
+        let text = self.documents.get(uri)?;
+        let line = position["line"].as_u64()? as usize;
+        let character = position["character"].as_u64()? as usize;
+        Some((text, offset_at(text, line, character)))
+    }
+}
+
+fn publish(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn diagnostic(text: &str, issue: &Issue, severity: u8) -> Value {
+    let (start, end) = match issue.span {
+        Some(span) => (span_offset(text, span.line, span.column), span_offset(text, span.end_line, span.end_column)),
+        None => (0, 0),
+    };
+    json!({
+        "range": { "start": position_at(text, start), "end": position_at(text, end) },
+        "severity": severity,
+        "source": "sigil",
+        "code": issue.kind,
+        "message": issue.message,
+    })
+}
+
+/// Byte offset of a 1-based line and character column.
+fn span_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = text.split_inclusive('\n').take(line.saturating_sub(1)).map(str::len).sum();
+    let rest = &text[line_start.min(text.len())..];
+    let line_text = rest.split('\n').next().unwrap_or_default();
+    line_start + line_text.chars().take(column.saturating_sub(1)).map(char::len_utf8).sum::<usize>()
+}
+
+/// Byte offset of a 0-based LSP line and UTF-16 character.
+fn offset_at(text: &str, line: usize, character: usize) -> usize {
+    let line_start: usize = text.split_inclusive('\n').take(line).map(str::len).sum();
+    let line_start = line_start.min(text.len());
+    let mut units = 0;
+    for (i, c) in text[line_start..].char_indices() {
+        if units >= character || c == '\n' {
+            return line_start + i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+/// The LSP position (0-based line, UTF-16 character) of a byte offset.
+fn position_at(text: &str, offset: usize) -> Value {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+    json!({ "line": line, "character": character })
+}
+
+/// The table the line starting at `line_start` belongs to, as a dotted path
+/// (`rules`, `rules.checks`, `trigger`), or None at the top level.
+fn current_table(text: &str, line_start: usize) -> Option<String> {
+    text[..line_start]
+        .lines()
+        .rev()
+        .find_map(|line| HEADER_RE.captures(line))
+        .map(|caps| caps[1].to_string())
+}
+
+/// The key whose value contains `offset`: the key of the innermost open array
+/// around it, or else the key assigned on its line.
+fn key_at(text: &str, offset: usize) -> Option<String> {
+    let before = &text[..offset];
+    let mut depth = 0usize;
+    for (i, c) in before.char_indices().rev() {
+        match c {
+            ']' => depth += 1,
+            '[' if depth > 0 => depth -= 1,
+            '[' => {
+                let line_start = before[..i].rfind('\n').map_or(0, |n| n + 1);
+                if let Some(caps) = ASSIGNMENT_RE.captures(&before[line_start..i]) {
+                    return Some(caps[1].to_string());
+                }
+                break;
+            }
+            _ => {}
+        }
+    }
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let (key, _) = before[line_start..].split_once('=')?;
+    let key = key.trim();
+    (!key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_')).then(|| key.to_string())
+}
+
+/// The contents of a string still open at the end of `prefix`, if any.
+fn open_string(prefix: &str) -> Option<&str> {
+    let mut open: Option<(char, usize)> = None;
+    for (i, c) in prefix.char_indices() {
+        match open {
+            None if c == '"' || c == '\'' => open = Some((c, i + 1)),
+            Some((quote, _)) if c == quote => open = None,
+            _ => {}
+        }
+    }
+    open.map(|(_, start)| &prefix[start..])
+}
+
+/// The string literal on the line around `offset`, with the byte range of its
+/// contents.
+fn string_at(text: &str, offset: usize) -> Option<(String, std::ops::Range<usize>)> {
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+    let mut open: Option<(char, usize)> = None;
+    for (i, c) in text[line_start..line_end].char_indices() {
+        let at = line_start + i;
+        match open {
+            None if c == '"' || c == '\'' => open = Some((c, at + 1)),
+            Some((quote, start)) if c == quote => {
+                if (start..=at).contains(&offset) {
+                    return Some((text[start..at].to_string(), start..at));
+                }
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Object schemas for a schema node, following `$ref`, array `items` and `oneOf`.
+fn objects(node: &Value) -> Vec<&Value> {
+    if let Some(name) = node["$ref"].as_str().and_then(|r| r.strip_prefix("#/$defs/")) {
+        return objects(&SCHEMA["$defs"][name]);
+    }
+    if let Some(variants) = node["oneOf"].as_array() {
+        return variants.iter().flat_map(objects).collect();
+    }
+    if node.get("items").is_some() {
+        return objects(&node["items"]);
+    }
+    if node.get("properties").is_some() { vec![node] } else { vec![] }
+}
+
+/// Object schemas of the table at the dotted path `table` in each of
+/// `schemas`; the contract itself when None.
+fn tables<'a>(schemas: &[&'a Value], table: Option<&str>) -> Vec<&'a Value> {
+    let mut nodes = schemas.to_vec();
+    for segment in table.into_iter().flat_map(|t| t.split('.')) {
+        nodes = nodes.into_iter().flat_map(|n| objects(&n["properties"][segment])).collect();
+    }
+    nodes
+}
+
+/// Field names allowed in the table at `table`, with their descriptions.
+fn field_choices(schemas: &[&Value], table: Option<&str>) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for (name, schema) in tables(schemas, table).iter().filter_map(|n| n["properties"].as_object()).flatten() {
+        if !fields.iter().any(|(f, _)| f == name) {
+            fields.push((name.clone(), schema["description"].as_str().unwrap_or_default().to_string()));
+        }
+    }
+    fields
+}
+
+/// Allowed values of `key` in the table at `table`: its enum, or the `const`
+/// of each variant (the `kind` of a check).
+fn value_choices(schemas: &[&Value], table: Option<&str>, key: &str) -> Vec<String> {
+    let mut values: Vec<String> = Vec::new();
+    for schema in tables(schemas, table).into_iter().filter_map(|n| n["properties"].get(key)) {
+        let listed = schema["enum"].as_array().cloned().unwrap_or_default();
+        for value in listed.iter().chain(schema.get("const")).filter_map(Value::as_str) {
+            if !values.iter().any(|v| v == value) {
+                values.push(value.to_string());
+            }
+        }
+    }
+    values
+}
+
+/// A project-relative path for a `file://` URI, or None for other schemes.
+fn uri_to_path(uri: &str) -> Option<String> {
+    let path = percent_decode(uri.strip_prefix("file://")?);
+    let cwd = std::env::current_dir().ok()?;
+    let cwd = cwd.display().to_string();
+    Some(match path.strip_prefix(&cwd) {
+        Some(rest) => rest.trim_start_matches('/').to_string(),
+        None => path,
+    })
+}
+
+fn path_to_uri(path: &str) -> String {
+    let absolute = std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.into());
+    let mut uri = String::from("file://");
+    for c in absolute.display().to_string().chars() {
+        match c {
+            ' ' | '%' | '#' | '?' => uri.push_str(&format!("%{:02X}", c as u32)),
+            c => uri.push(c),
+        }
+    }
+    uri
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = text.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(h, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_lsp_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn server(tag: &str) -> Server {
+        let config = Config { contracts_dir: temp_dir(tag).to_str().unwrap().to_string(), ..Config::default() };
+        Server::new(config, PathBuf::from(super::super::file_content::PROJECT_ROOT))
+    }
+
+    const URI_PATH: &str = "contracts/lsp-buffer.contract.toml";
+
+    fn open(server: &mut Server, text: &str) -> Vec<Value> {
+        let uri = path_to_uri(URI_PATH);
+        server.handle(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": uri, "languageId": "toml", "version": 1, "text": text } },
+        }))
+    }
+
+    fn request(server: &mut Server, method: &str, line: usize, character: usize) -> Value {
+        let uri = path_to_uri(URI_PATH);
+        let params = json!({ "textDocument": { "uri": uri }, "position": { "line": line, "character": character } });
+        server.handle(json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params })).remove(0)["result"].take()
+    }
+
+    fn labels(items: &Value) -> Vec<&str> {
+        items.as_array().unwrap().iter().map(|i| i["label"].as_str().unwrap()).collect()
+    }
+
+    const TEXT: &str = "id = \"lsp-buffer\"\nversion = \"1.0.0\"\nname = \"Buffer\"\ndescription = \"d\"\napplies_to = \"src/*.rs\"\nfiles = [\"src/main.rs\", \"src/gone.rs\"]\n\n[[rules]]\nid = \"r\"\ndescription = \"d\"\n\n[[rules]]\nid = \"r\"\ndescription = \"d\"\n";
+
+    #[test]
+    fn frames_messages_with_content_length() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({ "jsonrpc": "2.0", "id": 1, "result": "é" })).unwrap();
+        write_message(&mut buffer, &json!({ "jsonrpc": "2.0", "method": "exit" })).unwrap();
+        let mut input = std::io::Cursor::new(buffer);
+        assert_eq!(read_message(&mut input).unwrap().unwrap()["result"], "é");
+        assert_eq!(read_message(&mut input).unwrap().unwrap()["method"], "exit");
+        assert!(read_message(&mut input).unwrap().is_none());
+    }
+
+    #[test]
+    fn publishes_validation_diagnostics_for_the_buffer() {
+        let mut server = server("diagnostics");
+        let published = open(&mut server, TEXT);
+        let diagnostics = published[0]["params"]["diagnostics"].as_array().unwrap();
+        let missing = diagnostics.iter().find(|d| d["code"] == "missing_file").unwrap();
+        assert_eq!(missing["severity"], 1);
+        assert_eq!(missing["range"]["start"], json!({ "line": 5, "character": 24 }));
+        let duplicate = diagnostics.iter().find(|d| d["code"] == "duplicate_rule_id").unwrap();
+        assert_eq!(duplicate["range"]["start"]["line"], 12);
+
+        let broken = open(&mut server, "id = \"x\"\nversion = ");
+        assert_eq!(broken[0]["params"]["diagnostics"][0]["code"], "parse_error");
+    }
+
+    #[test]
+    fn rule_checks_run_on_open_and_save_but_not_on_change() {
+        let mut server = server("checks");
+        let text = format!("{TEXT}\n[[rules.checks]]\nkind = \"file_exists\"\npath = \"src/gone.rs\"\n");
+        let codes = |published: &[Value]| -> Vec<String> {
+            let diagnostics = published[0]["params"]["diagnostics"].as_array().unwrap();
+            diagnostics.iter().map(|d| d["code"].as_str().unwrap().to_string()).collect()
+        };
+        assert!(codes(&open(&mut server, &text)).contains(&"check_failed".to_string()));
+
+        let uri = path_to_uri(URI_PATH);
+        let changed = server.handle(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": { "textDocument": { "uri": uri, "version": 2 }, "contentChanges": [{ "text": text }] },
+        }));
+        let codes_on_change = codes(&changed);
+        assert!(codes_on_change.contains(&"duplicate_rule_id".to_string()));
+        assert!(!codes_on_change.contains(&"check_failed".to_string()), "{codes_on_change:?}");
+
+        let saved = server.handle(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didSave",
+            "params": { "textDocument": { "uri": uri } },
+        }));
+        assert!(codes(&saved).contains(&"check_failed".to_string()));
+    }
+
+    #[test]
+    fn completes_fields_values_and_paths() {
+        let mut server = server("completion");
+        let extension = json!({ "properties": { "owner": { "type": "string", "enum": ["team-a", "team-b"] } } });
+        server.config.schema.extension_schema = Some(crate::config::ExtensionSchema::compile(extension).unwrap());
+        let text = "id = \"lsp-buffer\"\npriority = \nfiles = [\"src/to\"]\n\n[[rules]]\n\n[[rules.checks]]\nkind = \"\n";
+        open(&mut server, text);
+        let fields = request(&mut server, "textDocument/completion", 0, 0);
+        assert!(labels(&fields).contains(&"priority") && labels(&fields).contains(&"owner"), "{fields}");
+        assert_eq!(labels(&request(&mut server, "textDocument/completion", 1, 11)), vec!["must", "should", "prefer"]);
+        assert_eq!(labels(&request(&mut server, "textDocument/completion", 2, 15)), vec!["src/tools/"]);
+        assert!(labels(&request(&mut server, "textDocument/completion", 5, 0)).contains(&"constraints"));
+        assert!(labels(&request(&mut server, "textDocument/completion", 7, 8)).contains(&"file_exists"));
+    }
+
+    #[test]
+    fn hovers_globs_and_jumps_to_files() {
+        let mut server = server("navigation");
+        open(&mut server, TEXT);
+        let hover = request(&mut server, "textDocument/hover", 4, 16);
+        assert!(hover["contents"]["value"].as_str().unwrap().contains("`src/main.rs`"), "{hover}");
+        let definition = request(&mut server, "textDocument/definition", 5, 12);
+        assert!(definition["uri"].as_str().unwrap().ends_with("/src/main.rs"));
+        assert_eq!(request(&mut server, "textDocument/definition", 5, 28), Value::Null);
+    }
+}
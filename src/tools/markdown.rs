@@ -0,0 +1,186 @@
+use super::report::{self, ContractRow};
+use super::reviews::{ReviewSession, Verdict};
+use super::validation::{Issue, Report};
+use crate::model::{Contract, Priority};
+use std::fmt::Write;
+
+/// Render a validation run, and optionally a review session, as a Markdown
+/// summary for PR descriptions: one table per priority, then every issue.
+pub fn render(contracts: &[Contract], validation: &Report, session: Option<&ReviewSession>) -> String {
+    let rows = report::rows(contracts, validation, session);
+    let mut out = String::from("## Sigil report\n\n");
+
+    let _ = write!(
+        out,
+        "**Validation:** {}, {}",
+        plural(validation.errors.len(), "error"),
+        plural(validation.warnings.len(), "warning")
+    );
+    if let Some(s) = session {
+        let count = |verdict: Verdict| rows.iter().filter(|r| r.verdict() == Some(verdict)).count();
+        let _ = write!(
+            out,
+            " · **Review:** `{}` ({} fail, {} needs human review, {} pass)",
+            s.review_id,
+            count(Verdict::Fail),
+            count(Verdict::NeedsHumanReview),
+            count(Verdict::Pass)
+        );
+    }
+    out.push_str("\n\n");
+
+    for (priority, heading) in [(Priority::Must, "Must"), (Priority::Should, "Should"), (Priority::Prefer, "Prefer")] {
+        let group: Vec<&ContractRow> = rows.iter().filter(|r| r.contract.priority == priority).collect();
+        if group.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "### {heading}\n");
+        if session.is_some() {
+            out.push_str("| Contract | Status | Validation | Verdict |\n|---|---|---|---|\n");
+        } else {
+            out.push_str("| Contract | Status | Validation |\n|---|---|---|\n");
+        }
+        for row in group {
+            let _ = write!(
+                out,
+                "| `{}` {} | {} | {} |",
+                row.contract.id,
+                cell(&row.contract.name),
+                super::label(&row.contract.status),
+                validation_cell(row)
+            );
+            if session.is_some() {
+                let verdict = row.verdict().map_or_else(|| "-".to_string(), |v| super::label(&v));
+                let _ = write!(out, " {verdict} |");
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+
+    let issues: Vec<(&str, &Issue)> = validation
+        .errors
+        .iter()
+        .map(|i| ("error", i))
+        .chain(validation.warnings.iter().map(|i| ("warning", i)))
+        .collect();
+    if !issues.is_empty() {
+        out.push_str("### Issues\n\n");
+        for (level, issue) in issues {
+            let _ = write!(out, "- **{level}** `{}`", issue.kind);
+            if let Some(id) = &issue.contract_id {
+                let _ = write!(out, " in `{id}`");
+            }
+            let _ = write!(out, ": {}", issue.message.replace('\n', " "));
+            if let Some(source) = &issue.source {
+                match issue.span {
+                    Some(span) => {
+                        let _ = write!(out, " (`{source}:{}:{}`)", span.line, span.column);
+                    }
+                    None => {
+                        let _ = write!(out, " (`{source}`)");
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+
+    if let Some(s) = session {
+        let open: Vec<_> = s.verdicts.iter().filter(|v| v.verdict != Verdict::Pass).collect();
+        if !open.is_empty() {
+            out.push_str("### Verdicts\n\n");
+            for v in open {
+                let target = match &v.rule_id {
+                    Some(rule) => format!("{}/{rule}", v.contract_id),
+                    None => v.contract_id.clone(),
+                };
+                let _ = writeln!(
+                    out,
+                    "- **{}** `{target}`: {}",
+                    super::label(&v.verdict),
+                    v.rationale.replace('\n', " ")
+                );
+            }
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+fn validation_cell(row: &ContractRow) -> String {
+    match (row.errors.len(), row.warnings.len()) {
+        (0, 0) => "ok".to_string(),
+        (e, 0) => plural(e, "error"),
+        (0, w) => plural(w, "warning"),
+        (e, w) => format!("{}, {}", plural(e, "error"), plural(w, "warning")),
+    }
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    format!("{n} {noun}{}", if n == 1 { "" } else { "s" })
+}
+
+/// Keep free text from breaking the table.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::reviews::VerdictRecord;
+
+    fn contract(id: &str, priority: &str) -> Contract {
+        toml::from_str(&format!(
+            "id = \"{id}\"\nversion = \"1.0.0\"\nname = \"Name | {id}\"\ndescription = \"d\"\npriority = \"{priority}\"\n"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn groups_contracts_by_priority() {
+        let contracts = vec![contract("a", "must"), contract("b", "prefer")];
+        let report = Report {
+            errors: vec![Issue {
+                kind: "missing_file",
+                contract_id: Some("a".to_string()),
+                message: "gone".to_string(),
+                file: None,
+                source: None,
+                span: None,
+            }],
+            warnings: vec![],
+            ..Report::default()
+        };
+        let md = render(&contracts, &report, None);
+        assert!(md.contains("**Validation:** 1 error, 0 warnings"));
+        assert!(md.contains("### Must\n\n| Contract | Status | Validation |"));
+        assert!(md.contains("| `a` Name \\| a | active | 1 error |"));
+        assert!(!md.contains("### Should"));
+        assert!(md.find("### Must").unwrap() < md.find("### Prefer").unwrap());
+        assert!(md.contains("- **error** `missing_file` in `a`: gone"));
+    }
+
+    #[test]
+    fn includes_verdict_column_and_open_verdicts() {
+        let contracts = vec![contract("a", "should")];
+        let session = ReviewSession {
+            review_id: "pr-1".to_string(),
+            verdicts: vec![VerdictRecord {
+                contract_id: "a".to_string(),
+                rule_id: None,
+                verdict: Verdict::Fail,
+                rationale: "logs emails".to_string(),
+                evidence: vec![],
+                recorded_at: String::new(),
+            }],
+        };
+        let md = render(&contracts, &Report::default(), Some(&session));
+        assert!(md.contains("**Review:** `pr-1` (1 fail, 0 needs human review, 0 pass)"));
+        assert!(md.contains("| `a` Name \\| a | active | ok | fail |"));
+        assert!(md.contains("- **fail** `a`: logs emails"));
+    }
+}
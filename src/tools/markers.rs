@@ -0,0 +1,203 @@
+use super::locate::Span;
+use crate::model::Contract;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// A `sigil:` marker in a comment, naming a contract and optionally one of its
+/// rules: `// sigil: <contract-id>` above a block of code binds the block to
+/// the contract, `// sigil: <contract-id>/<rule-id>` above a test links it to
+/// the rule.
+static MARKER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?://|#|--|/\*|\*|<!--)\s*sigil:\s*([a-z0-9]+(?:-[a-z0-9]+)*)(?:/([a-z0-9]+(?:-[a-z0-9]+)*))?")
+        .unwrap()
+});
+
+/// Extensions of source files scanned for markers.
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "mjs", "cjs", "py", "go", "java", "kt", "rb", "cs", "c", "h", "cc", "cpp",
+    "hpp", "swift", "scala", "php", "sh", "sql", "yaml", "yml",
+];
+
+/// Lines that close a block at the indentation it was opened at.
+const CLOSERS: &[&str] = &["}", ")", "]", "end"];
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Marker {
+    pub file: String,
+    /// 1-based line of the marker.
+    pub line: usize,
+    pub contract_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    /// The block the marker annotates: from the first non-blank line after the
+    /// marker to the end of that block, judged by indentation.
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Where the marker text is, for diagnostics.
+    #[serde(skip)]
+    pub span: Span,
+}
+
+/// Whether `path` is a source file that may carry markers.
+pub fn is_source(path: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SOURCE_EXTENSIONS.contains(&e))
+}
+
+/// Markers in `text`, attributed to `file`.
+pub fn scan_text(file: &str, text: &str) -> Vec<Marker> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut markers = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        for caps in MARKER_RE.captures_iter(line) {
+            let whole = caps.get(0).unwrap();
+            let column = line[..whole.start()].chars().count() + 1;
+            let (start, end) = region(&lines, i);
+            markers.push(Marker {
+                file: file.to_string(),
+                line: i + 1,
+                contract_id: caps[1].to_string(),
+                rule_id: caps.get(2).map(|m| m.as_str().to_string()),
+                start_line: start + 1,
+                end_line: end + 1,
+                span: Span {
+                    line: i + 1,
+                    column,
+                    end_line: i + 1,
+                    end_column: column + whole.as_str().chars().count(),
+                },
+            });
+        }
+    }
+    markers
+}
+
+/// 0-based first and last line of the block annotated by a marker on line
+/// `marker`. The block starts at the next non-blank line and takes in every
+/// following line indented deeper than that one, plus a closing bracket at
+/// the same indentation. Attributes and decorators (`#[...]`, `@...`) at the
+/// start extend the block to the item they decorate.
+fn region(lines: &[&str], marker: usize) -> (usize, usize) {
+    let Some(start) = (marker + 1..lines.len()).find(|&i| !lines[i].trim().is_empty()) else {
+        return (marker, marker);
+    };
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let base = indent(lines[start]);
+    let mut end = start;
+    let mut deeper = false;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if indent(line) > base {
+            deeper = true;
+            end = i;
+            continue;
+        }
+        if indent(line) < base {
+            break;
+        }
+        if deeper && CLOSERS.iter().any(|c| trimmed.starts_with(c)) {
+            end = i;
+            break;
+        }
+        let previous = lines[end].trim();
+        if !deeper && (previous.starts_with("#[") || previous.starts_with('@')) {
+            end = i;
+            continue;
+        }
+        break;
+    }
+    (start, end)
+}
+
+/// Markers in every source file of `files`, paths relative to the project
+/// `root`, skipping the contracts directory. Files that do not exist, are not
+/// text, or resolve outside the root are skipped.
+pub fn scan(root: &Path, files: &[String], contracts_dir: &str) -> Vec<Marker> {
+    let contracts_dir = format!("{}/", contracts_dir.trim_end_matches('/').trim_start_matches("./"));
+    files
+        .iter()
+        .filter(|f| is_source(f) && !f.starts_with(&contracts_dir))
+        .filter_map(|f| {
+            let resolved = super::file_content::resolve(root, f).ok()?;
+            std::fs::read_to_string(resolved).ok().map(|text| scan_text(f, &text))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Why a marker does not resolve, or None if it names an existing contract
+/// (and rule, when it has one).
+pub fn unknown_reason(marker: &Marker, contracts: &[Contract]) -> Option<String> {
+    let Some(contract) = contracts.iter().find(|c| c.id == marker.contract_id) else {
+        return Some(format!("Unknown contract '{}'", marker.contract_id));
+    };
+    let rule_id = marker.rule_id.as_deref()?;
+    if contract.rules.iter().flatten().any(|r| r.id == rule_id) {
+        None
+    } else {
+        Some(format!("Unknown rule '{rule_id}' in contract '{}'", contract.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Markers in these tests are built at runtime so this file does not carry any itself.
+    const TAG: &str = "sigil:";
+
+    #[test]
+    fn finds_markers_in_comments_only() {
+        let text = format!("// {TAG} no-pii/logs\nfn f() {{}}\n    # {TAG} config-loading\nlet s = \"{TAG} not-a/marker\";\n");
+        let markers = scan_text("a.rs", &text);
+        assert_eq!(markers.len(), 2);
+        assert_eq!(markers[0].line, 1);
+        assert_eq!(markers[0].rule_id.as_deref(), Some("logs"));
+        assert_eq!(markers[1].contract_id, "config-loading");
+        assert_eq!(markers[1].rule_id, None);
+        assert_eq!(markers[1].span.column, 5);
+    }
+
+    #[test]
+    fn regions_follow_indentation() {
+        let rust = format!(
+            "// {TAG} no-pii\n#[instrument]\nfn log(user: &User) {{\n    info!(\"x\");\n\n    info!(\"y\");\n}}\n\nfn other() {{}}\n"
+        );
+        let marker = &scan_text("a.rs", &rust)[0];
+        assert_eq!((marker.start_line, marker.end_line), (2, 7));
+
+        let python = format!("class A:\n    # {TAG} no-pii\n    def f(self):\n        pass\n    def g(self):\n        pass\n");
+        let marker = &scan_text("a.py", &python)[0];
+        assert_eq!((marker.start_line, marker.end_line), (3, 4));
+
+        let single = format!("# {TAG} no-pii\nX = 1\nY = 2\n");
+        let marker = &scan_text("a.py", &single)[0];
+        assert_eq!((marker.start_line, marker.end_line), (2, 2));
+    }
+
+    #[test]
+    fn markers_must_name_known_contracts_and_rules() {
+        let contracts: Vec<Contract> = vec![
+            toml::from_str("id = \"c\"\nversion = \"1\"\nname = \"c\"\ndescription = \"d\"\n[[rules]]\nid = \"r\"\ndescription = \"d\"\n")
+                .unwrap(),
+        ];
+        let marker = |target: &str| scan_text("a.rs", &format!("// {TAG} {target}\n")).remove(0);
+        assert_eq!(unknown_reason(&marker("c/r"), &contracts), None);
+        assert_eq!(unknown_reason(&marker("c"), &contracts), None);
+        assert!(unknown_reason(&marker("c/x"), &contracts).is_some());
+        assert!(unknown_reason(&marker("x"), &contracts).is_some());
+    }
+
+    #[test]
+    fn only_source_files_are_scanned() {
+        assert!(is_source("src/main.rs") && is_source("tests/test_api.py"));
+        assert!(!is_source("README.md") && !is_source("contracts/x.contract.toml"));
+    }
+}
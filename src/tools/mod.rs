@@ -1,14 +1,46 @@
+mod check_overrides;
+mod checks;
 mod create_contract;
 mod delete_contract;
-mod get_notes;
-mod loader;
+pub(crate) mod detect_contract_changes;
+mod diff_contracts;
+pub(crate) mod evaluate_gate;
+pub(crate) mod export_report;
+mod file_content;
+mod format;
+pub(crate) mod format_contracts;
+mod gate;
 mod get_affected_contracts;
 mod get_contract;
+mod get_notes;
+mod git;
+mod imports;
+mod junit;
 mod list_contracts;
+mod list_templates;
+mod loader;
+mod locate;
+pub(crate) mod lsp;
+mod markdown;
+mod markers;
+mod overrides;
+mod record_verdict;
+mod redaction;
+mod repo_files;
+mod report;
 mod review_changeset;
+mod review_summary;
+mod reviews;
+mod rule_test_coverage;
+mod sarif;
+mod schema;
+mod semantic_diff;
+mod templates;
+mod triggers;
 mod update_contract;
 mod validate_all_contracts;
 mod validate_contract;
+mod validation;
 
 use rmcp::{
     ServerHandler,
@@ -17,6 +49,7 @@ use rmcp::{
     tool, tool_handler, tool_router,
 };
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 use crate::config::Config;
@@ -32,6 +65,8 @@ struct SessionState {
 pub struct SigilServer {
     pub tool_router: ToolRouter<SigilServer>,
     pub config: Config,
+    /// Directory that file references are relative to.
+    root: PathBuf,
     session: Mutex<SessionState>,
 }
 
@@ -50,7 +85,19 @@ pub(super) fn error_response(msg: impl std::fmt::Display) -> String {
     serde_json::json!({"error": msg.to_string()}).to_string()
 }
 
+/// The serialized (snake_case) name of an enum value, e.g. `Priority::Must` -> "must".
+pub(super) fn label<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
 impl SigilServer {
+    pub(super) fn root(&self) -> &Path {
+        &self.root
+    }
+
     pub(super) fn require_listed(&self, tool: &str, contract_id: &str) -> Result<(), String> {
         if !self.session.lock().unwrap().listed {
             Err(error_response(format!(
@@ -90,6 +137,7 @@ impl SigilServer {
         Self {
             tool_router: Self::tool_router(),
             config,
+            root: PathBuf::from(file_content::PROJECT_ROOT),
             session: Mutex::new(SessionState::default()),
         }
     }
@@ -102,7 +150,7 @@ impl SigilServer {
         get_notes::handle(self, params).await
     }
 
-    #[tool(description = "List all contracts with summary info. Starting point for planning. Supports optional filtering by domain and/or tags. Call this before sigil_get_contract.")]
+    #[tool(description = "List all contracts with summary info. Starting point for planning. Supports optional filtering by domain, tags, and/or trigger fields (e.g. {\"topic\": \"device-data\"}). Call this before sigil_get_contract.")]
     async fn sigil_list_contracts(
         &self,
         Parameters(params): Parameters<list_contracts::Params>,
@@ -118,7 +166,7 @@ impl SigilServer {
         get_contract::handle(self, params).await
     }
 
-    #[tool(description = "Given a list of file paths, return all contracts that care about those files via files, applies_to glob patterns, or matching rules. Use this during planning to understand contract implications of a change.")]
+    #[tool(description = "Given a list of file paths, return all contracts that care about those files via files, applies_to glob patterns, matching rules, or sigil: annotations in the files' comments, with any waivers covering the matched files. With include_indirect, also matches contracts of files that import the given files within max_hops imports, labelled indirect with the import chain. Use this during planning to understand contract implications of a change.")]
     async fn sigil_get_affected_contracts(
         &self,
         Parameters(params): Parameters<get_affected_contracts::Params>,
@@ -134,7 +182,15 @@ impl SigilServer {
         validate_contract::handle(self, params).await
     }
 
-    #[tool(description = "Create a new contract file. Validates the contract against the schema before writing. Derives the filename from the contract id field. Fails if a contract with that id already exists.")]
+    #[tool(description = "List the contract templates in the contracts dir's templates/ subdirectory, with each template's description and the variables its placeholders need. Use one with sigil_create_contract so new contracts follow the house style.")]
+    async fn sigil_list_templates(
+        &self,
+        Parameters(params): Parameters<list_templates::Params>,
+    ) -> String {
+        list_templates::handle(self, params).await
+    }
+
+    #[tool(description = "Create a new contract file, either from the full contract or by rendering a template (see sigil_list_templates) with values for its variables. Validates the contract against the schema, and its trigger against any schema configured for its trigger type, before writing. Derives the filename from the contract id field. Fails if a contract with that id already exists.")]
     async fn sigil_create_contract(
         &self,
         Parameters(params): Parameters<create_contract::Params>,
@@ -142,7 +198,7 @@ impl SigilServer {
         create_contract::handle(self, params).await
     }
 
-    #[tool(description = "Apply partial updates to an existing contract. Unspecified fields are preserved. List fields are replaced wholesale. Returns a diff of what changed. Requires a prior sigil_get_contract call for this contract_id in the current session.")]
+    #[tool(description = "Apply partial updates to an existing contract. Unspecified fields are preserved. List fields are replaced wholesale. The result is validated against the contract schema and any schema configured for its trigger type. Returns a text diff and a structured semantic diff of what changed. Requires a prior sigil_get_contract call for this contract_id in the current session.")]
     async fn sigil_update_contract(
         &self,
         Parameters(params): Parameters<update_contract::Params>,
@@ -150,7 +206,7 @@ impl SigilServer {
         update_contract::handle(self, params).await
     }
 
-    #[tool(description = "Delete a contract. Requires a prior sigil_get_contract call for this contract_id in the current session.")]
+    #[tool(description = "Retire a contract. mode \"delete\" (default) removes the file and warns if the contract is an active must contract or is referenced by other contracts, and rejects reason and superseded_by; \"deprecate\" keeps it with status deprecated and a required reason (and optional superseded_by); \"archive\" deprecates it and moves it to the archive/ subdirectory, which is never loaded and whose files are never replaced. Requires a prior sigil_get_contract call for this contract_id in the current session.")]
     async fn sigil_delete_contract(
         &self,
         Parameters(params): Parameters<delete_contract::Params>,
@@ -158,7 +214,31 @@ impl SigilServer {
         delete_contract::handle(self, params).await
     }
 
-    #[tool(description = "Fast validation of all contracts: checks missing files and schema validation errors. Returns pass/fail boolean plus categorized errors and warnings.")]
+    #[tool(description = "Rewrite contract files in the canonical layout: keys in the documented order, description and notes as multi-line strings, sorted unique tags, and one array element per line, keeping comments. With check true, only lists the files that are not formatted. Optionally limited to one contract_id.")]
+    async fn sigil_format_contracts(
+        &self,
+        Parameters(params): Parameters<format_contracts::Params>,
+    ) -> String {
+        format_contracts::handle(self, params).await
+    }
+
+    #[tool(description = "Structured diff between two versions of a contract: field changes, rules added/removed/renamed, constraint changes, applies_to widening/narrowing, and priority/status transitions. Compares a contract file against another file (base_path) or against its version at a git ref (base_ref).")]
+    async fn sigil_diff_contracts(
+        &self,
+        Parameters(params): Parameters<diff_contracts::Params>,
+    ) -> String {
+        diff_contracts::handle(self, params).await
+    }
+
+    #[tool(description = "Compare every contract in the contracts directory against a base git ref and report each change as weakening (e.g. removed constraint, narrowed applies_to, lowered priority, deprecation), strengthening (e.g. added rule or contract), or neutral. Intended for CI gates that require human approval for weakening changes.")]
+    async fn sigil_detect_contract_changes(
+        &self,
+        Parameters(params): Parameters<detect_contract_changes::Params>,
+    ) -> String {
+        detect_contract_changes::handle(self, params).await
+    }
+
+    #[tool(description = "Fast validation of all contracts: checks missing files, schema validation errors, and expired or dangling waivers. Returns pass/fail boolean plus categorized errors and warnings.")]
     async fn sigil_validate_all_contracts(
         &self,
         Parameters(params): Parameters<validate_all_contracts::Params>,
@@ -166,11 +246,59 @@ impl SigilServer {
         validate_all_contracts::handle(self, params).await
     }
 
-    #[tool(description = "Bundle context for a changeset review. Given changed files and optional diff, returns affected contracts with full context (contract content and file contents). The agent then performs the semantic review and produces verdicts.")]
+    #[tool(description = "Bundle context for a changeset review. Given changed files and optional diff, returns affected contracts with full context (contract content and file contents). The agent then performs the semantic review and produces verdicts. With max_tokens, contracts are packed must first and matched files first, and a next_cursor is returned for the rest; pass it back as cursor with the same files to get the next page.")]
     async fn sigil_review_changeset(
         &self,
         Parameters(params): Parameters<review_changeset::Params>,
     ) -> String {
         review_changeset::handle(self, params).await
     }
+
+    #[tool(description = "Record a review verdict (pass, fail, or needs_human_review) for a contract or one of its rules, with a rationale and optional evidence file/line references. Verdicts are stored in a review session file identified by review_id; recording again for the same contract and rule replaces the earlier verdict. Requires a prior sigil_get_contract or sigil_review_changeset call covering this contract_id in the current session.")]
+    async fn sigil_record_verdict(
+        &self,
+        Parameters(params): Parameters<record_verdict::Params>,
+    ) -> String {
+        record_verdict::handle(self, params).await
+    }
+
+    #[tool(description = "Aggregate the verdicts recorded for a review session. Returns each reviewed contract's worst verdict and enforcement level, and an overall pass/fail: failing or needs_human_review verdicts on active must contracts fail the review; should, prefer, draft and deprecated contracts never do.")]
+    async fn sigil_review_summary(
+        &self,
+        Parameters(params): Parameters<review_summary::Params>,
+    ) -> String {
+        review_summary::handle(self, params).await
+    }
+
+    #[tool(description = "Parse sigil-override directives (`sigil-override: <contract-id> -- \"reason\"`) from text or a file, such as exported PR comments. Returns accepted overrides, rejected ones (unknown contract, contract with overridable = false, duplicate), and malformed directive lines.")]
+    async fn sigil_check_overrides(
+        &self,
+        Parameters(params): Parameters<check_overrides::Params>,
+    ) -> String {
+        check_overrides::handle(self, params).await
+    }
+
+    #[tool(description = "Evaluate the CI gate: combines validation issues with the verdicts recorded for an optional review session and applies the priority/status enforcement mapping (must blocks, should warns, prefer informs, draft ignored, deprecated warns; overridable per branch in sigil.config.toml). Accepted sigil-override directives waive failing contracts, with the reason recorded. Returns pass/fail, an exit code, and a per-contract explanation.")]
+    async fn sigil_evaluate_gate(
+        &self,
+        Parameters(params): Parameters<evaluate_gate::Params>,
+    ) -> String {
+        evaluate_gate::handle(self, params).await
+    }
+
+    #[tool(description = "Report which contract rules are confirmed by tests. A rule is linked through its tests field (a file path, or path::test_name) or through a comment marker 'sigil: <contract-id>/<rule-id>' in test code (test files, #[cfg(test)] modules, or above a test item). A test name must appear in test code of its file. Lists untested rules, tests entries pointing at missing files or tests, and markers naming unknown contracts or rules. Optionally limited to one contract_id.")]
+    async fn sigil_rule_test_coverage(
+        &self,
+        Parameters(params): Parameters<rule_test_coverage::Params>,
+    ) -> String {
+        rule_test_coverage::handle(self, params).await
+    }
+
+    #[tool(description = "Export validation issues, and the verdicts recorded for an optional review session, as a report. Format sarif returns a SARIF 2.1.0 log with one rule per issue kind or contract rule and locations in contract files or verdict evidence; junit returns JUnit XML with one testcase per contract; markdown returns a summary grouped by priority, ready to paste into a PR description.")]
+    async fn sigil_export_report(
+        &self,
+        Parameters(params): Parameters<export_report::Params>,
+    ) -> String {
+        export_report::handle(self, params).await
+    }
 }
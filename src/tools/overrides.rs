@@ -0,0 +1,147 @@
+use crate::model::Contract;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+const MARKER: &str = "sigil-override:";
+
+/// `sigil-override: <contract-id> -- "reason"`, anywhere on a line (so quoted
+/// or list-formatted PR comments still parse).
+static OVERRIDE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"sigil-override:\s*([a-z0-9]+(?:-[a-z0-9]+)*)\s+--\s+"((?:[^"\\]|\\.)*)""#).unwrap()
+});
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Override {
+    pub contract_id: String,
+    pub reason: String,
+    /// 1-based line in the override text.
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Rejected {
+    pub contract_id: String,
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Malformed {
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct OverrideReport {
+    pub accepted: Vec<Override>,
+    pub rejected: Vec<Rejected>,
+    pub malformed: Vec<Malformed>,
+}
+
+impl OverrideReport {
+    pub fn is_empty(&self) -> bool {
+        self.accepted.is_empty() && self.rejected.is_empty() && self.malformed.is_empty()
+    }
+
+    pub fn reason_for(&self, contract_id: &str) -> Option<&str> {
+        self.accepted
+            .iter()
+            .find(|o| o.contract_id == contract_id)
+            .map(|o| o.reason.as_str())
+    }
+}
+
+/// Extract every override directive from `text`. Lines mentioning the marker
+/// that do not match the expected form are returned as malformed.
+pub fn parse(text: &str) -> (Vec<Override>, Vec<Malformed>) {
+    let mut overrides = Vec::new();
+    let mut malformed = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if !line.contains(MARKER) {
+            continue;
+        }
+        match OVERRIDE_RE.captures(line) {
+            Some(caps) if !caps[2].trim().is_empty() => overrides.push(Override {
+                contract_id: caps[1].to_string(),
+                reason: caps[2].replace("\\\"", "\""),
+                line: i + 1,
+            }),
+            _ => malformed.push(Malformed { line: i + 1, text: line.trim().to_string() }),
+        }
+    }
+    (overrides, malformed)
+}
+
+/// Parse `text` and accept only overrides of existing contracts that allow
+/// being overridden. Only the first override per contract is accepted.
+pub fn resolve(text: &str, contracts: &[Contract]) -> OverrideReport {
+    let (parsed, malformed) = parse(text);
+    let mut report = OverrideReport { malformed, ..OverrideReport::default() };
+
+    for o in parsed {
+        let rejection = match contracts.iter().find(|c| c.id == o.contract_id) {
+            None => Some(format!("Unknown contract '{}'", o.contract_id)),
+            Some(c) if !c.is_overridable() => {
+                Some(format!("Contract '{}' does not allow overrides (overridable = false)", c.id))
+            }
+            Some(_) if report.reason_for(&o.contract_id).is_some() => {
+                Some(format!("Contract '{}' is already overridden on an earlier line", o.contract_id))
+            }
+            Some(_) => None,
+        };
+        match rejection {
+            Some(message) => report.rejected.push(Rejected {
+                contract_id: o.contract_id,
+                line: o.line,
+                message,
+            }),
+            None => report.accepted.push(o),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(id: &str, overridable: Option<bool>) -> Contract {
+        let mut c: Contract = toml::from_str(&format!(
+            "id = \"{id}\"\nversion = \"1.0.0\"\nname = \"{id}\"\ndescription = \"d\"\n"
+        ))
+        .unwrap();
+        c.overridable = overridable;
+        c
+    }
+
+    #[test]
+    fn parses_overrides_embedded_in_comment_text() {
+        let text = "LGTM\n> sigil-override: no-pii-in-logs -- \"debug build only\"\n";
+        let (overrides, malformed) = parse(text);
+        assert!(malformed.is_empty());
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].contract_id, "no-pii-in-logs");
+        assert_eq!(overrides[0].reason, "debug build only");
+        assert_eq!(overrides[0].line, 2);
+    }
+
+    #[test]
+    fn reports_malformed_directives() {
+        let text = "sigil-override: no-pii-in-logs\nsigil-override: x -- \"\"\n";
+        let (overrides, malformed) = parse(text);
+        assert!(overrides.is_empty());
+        assert_eq!(malformed.len(), 2);
+    }
+
+    #[test]
+    fn rejects_unknown_and_non_overridable_contracts() {
+        let contracts = vec![contract("open", None), contract("locked", Some(false))];
+        let text = "sigil-override: open -- \"ok\"\nsigil-override: locked -- \"no\"\nsigil-override: ghost -- \"no\"\nsigil-override: open -- \"again\"\n";
+        let report = resolve(text, &contracts);
+        assert_eq!(report.accepted.len(), 1);
+        assert_eq!(report.accepted[0].contract_id, "open");
+        assert_eq!(report.rejected.len(), 3);
+        assert_eq!(report.reason_for("open"), Some("ok"));
+    }
+}
@@ -0,0 +1,71 @@
+use super::reviews::{self, Evidence, Verdict, VerdictRecord};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {
+    /// Review session id (e.g. a PR number or CI run id). Verdicts are stored per session.
+    pub review_id: String,
+    /// The contract the verdict is about.
+    pub contract_id: String,
+    /// Optional rule within the contract. Omit for a verdict on the contract as a whole.
+    pub rule_id: Option<String>,
+    /// "pass", "fail", or "needs_human_review".
+    pub verdict: Verdict,
+    /// Why the agent reached this verdict.
+    pub rationale: String,
+    /// Files and line ranges supporting the verdict.
+    pub evidence: Option<Vec<Evidence>>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    path: String,
+    replaced: bool,
+    recorded: VerdictRecord,
+}
+
+pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    if let Err(e) = server.require_read("sigil_record_verdict", &params.contract_id) {
+        return e;
+    }
+
+    let (contracts, _) = super::loader::load_contracts(&server.config.contracts_dir);
+    let contract = match super::loader::find_unique(&contracts, &params.contract_id) {
+        Ok(Some(c)) => c,
+        Ok(None) => return super::error_response(format!("Contract '{}' not found", params.contract_id)),
+        Err(e) => return super::error_response(e),
+    };
+    if let Some(rule_id) = &params.rule_id {
+        let known = contract.rules.iter().flatten().any(|r| &r.id == rule_id);
+        if !known {
+            return super::error_response(format!(
+                "Contract '{}' has no rule '{rule_id}'",
+                params.contract_id
+            ));
+        }
+    }
+    if params.rationale.trim().is_empty() {
+        return super::error_response("rationale must not be empty");
+    }
+
+    let mut session = match reviews::load(&server.config.reviews_dir, &params.review_id) {
+        Ok(s) => s,
+        Err(e) => return super::error_response(e),
+    };
+
+    let recorded = VerdictRecord {
+        contract_id: params.contract_id,
+        rule_id: params.rule_id,
+        verdict: params.verdict,
+        rationale: params.rationale,
+        evidence: params.evidence.unwrap_or_default(),
+        recorded_at: chrono::Local::now().to_rfc3339(),
+    };
+    let replaced = session.record(recorded.clone());
+
+    match reviews::save(&server.config.reviews_dir, &session) {
+        Ok(path) => serde_json::to_string(&Response { path, replaced, recorded }).unwrap(),
+        Err(e) => super::error_response(e),
+    }
+}
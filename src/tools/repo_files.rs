@@ -0,0 +1,48 @@
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Every file in the project at `root`, relative to it. Uses git so ignored
+/// files (build output, dependencies) are left out; outside a git repository,
+/// walks the directory tree skipping hidden directories and `target`.
+pub fn list(root: &Path) -> Vec<String> {
+    match super::git::working_tree_files(root) {
+        // Deleted files stay in the index until the deletion is staged.
+        Ok(files) => files.into_iter().filter(|f| root.join(f).is_file()).collect(),
+        Err(_) => walk(root),
+    }
+}
+
+fn walk(root: &Path) -> Vec<String> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0 || !(name.starts_with('.') || name == "target")
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| {
+            let path = e.path().strip_prefix(root).unwrap_or(e.path());
+            path.display().to_string().replace('\\', "/")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_this_crate_without_build_output() {
+        let files = list(Path::new("."));
+        assert!(files.iter().any(|f| f == "src/main.rs"));
+        assert!(!files.iter().any(|f| f.starts_with("target/")));
+    }
+
+    #[test]
+    fn walk_skips_hidden_and_target_directories() {
+        let files = walk(Path::new("."));
+        assert!(files.iter().any(|f| f == "Cargo.toml"));
+        assert!(!files.iter().any(|f| f.starts_with(".git/") || f.starts_with("target/")));
+    }
+}
@@ -0,0 +1,54 @@
+use super::reviews::{ReviewSession, Verdict, VerdictRecord};
+use super::validation::{Issue, Report};
+use crate::model::Contract;
+
+/// Everything the report renderers know about one contract.
+pub struct ContractRow<'a> {
+    pub contract: &'a Contract,
+    pub errors: Vec<&'a Issue>,
+    pub warnings: Vec<&'a Issue>,
+    pub verdicts: Vec<&'a VerdictRecord>,
+}
+
+impl ContractRow<'_> {
+    /// Worst verdict recorded for the contract or any of its rules.
+    pub fn verdict(&self) -> Option<Verdict> {
+        self.verdicts.iter().map(|v| v.verdict).max()
+    }
+}
+
+/// One row per contract, in contract order, with its issues and verdicts attached.
+pub fn rows<'a>(
+    contracts: &'a [Contract],
+    validation: &'a Report,
+    session: Option<&'a ReviewSession>,
+) -> Vec<ContractRow<'a>> {
+    let attributed = |issues: &'a [Issue], id: &str| -> Vec<&'a Issue> {
+        issues.iter().filter(|i| i.contract_id.as_deref() == Some(id)).collect()
+    };
+    contracts
+        .iter()
+        .map(|contract| ContractRow {
+            contract,
+            errors: attributed(&validation.errors, &contract.id),
+            warnings: attributed(&validation.warnings, &contract.id),
+            verdicts: session
+                .iter()
+                .flat_map(|s| &s.verdicts)
+                .filter(|v| v.contract_id == contract.id)
+                .collect(),
+        })
+        .collect()
+}
+
+/// Issues not attributed to any loaded contract, e.g. parse failures or
+/// waivers naming an unknown contract.
+pub fn unattributed<'a>(contracts: &[Contract], issues: &'a [Issue]) -> Vec<&'a Issue> {
+    issues
+        .iter()
+        .filter(|i| match &i.contract_id {
+            None => true,
+            Some(id) => !contracts.iter().any(|c| &c.id == id),
+        })
+        .collect()
+}
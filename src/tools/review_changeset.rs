@@ -1,8 +1,8 @@
+use super::file_content;
 use crate::model::{Contract, Priority, Status};
 use globset::Glob;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct Params {
@@ -10,6 +10,12 @@ pub struct Params {
     pub files: Vec<String>,
     /// Optional diff string for additional context (not interpreted by the server).
     pub diff: Option<String>,
+    /// Approximate size budget for this response in tokens (about 4 bytes each).
+    /// Contracts are packed must first, then should, then prefer; what does not
+    /// fit is left for the next page.
+    pub max_tokens: Option<usize>,
+    /// The next_cursor of a previous response, to fetch the following page.
+    pub cursor: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -18,6 +24,9 @@ struct Response {
     total: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     diff: Option<String>,
+    /// Pass as `cursor` to continue where this page stopped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
     warnings: Vec<String>,
 }
 
@@ -33,24 +42,24 @@ struct AffectedEntry {
     tags: Option<Vec<String>>,
     trigger_type: Option<String>,
     matched_files: Vec<String>,
+    /// True when earlier files of this contract were returned on a previous page.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    continued: bool,
     contract: Contract,
-    file_contents: HashMap<String, FileContent>,
+    /// Matched files first, then the rest of the contract's files.
+    file_contents: serde_json::Map<String, serde_json::Value>,
 }
 
-#[derive(Serialize)]
-#[serde(tag = "status", rename_all = "snake_case")]
-enum FileContent {
-    Ok { contents: String },
-    Missing,
-    Error { message: String },
-}
+/// Bytes per token used to turn `max_tokens` into a byte budget.
+const BYTES_PER_TOKEN: usize = 4;
 
 pub async fn handle(server: &super::SigilServer, params: Params) -> String {
     let (contracts, mut warnings) = super::loader::load_contracts(&server.config.contracts_dir);
     server.mark_listed();
 
     let files: Vec<String> = params.files.iter().map(|f| f.replace("\\", "/")).collect();
-    let mut entries = Vec::new();
+    let annotations = super::markers::scan(server.root(), &files, &server.config.contracts_dir);
+    let mut affected = Vec::new();
 
     for contract in contracts {
         let contract_files: Vec<&str> = contract.all_files();
@@ -80,27 +89,45 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
             }
         }
 
-        if matched.is_empty() {
-            continue;
+        // Files with an annotation naming the contract
+        for marker in annotations.iter().filter(|m| m.contract_id == contract.id) {
+            if !matched.contains(&marker.file) {
+                matched.push(marker.file.clone());
+            }
         }
 
-        // Retrieve file contents
-        let mut file_contents = HashMap::new();
-        for path in contract.all_files() {
-            let resolved = match std::fs::read_to_string(path) {
-                Ok(contents) => FileContent::Ok { contents },
-                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                    warnings.push(format!("Contract '{}': missing file '{path}'", contract.id));
-                    FileContent::Missing
-                }
-                Err(e) => FileContent::Error { message: e.to_string() },
-            };
-            file_contents.insert(path.to_string(), resolved);
+        if !matched.is_empty() {
+            affected.push((contract, matched));
         }
+    }
+    affected.sort_by(|(a, _), (b, _)| {
+        priority_order(&a.priority)
+            .cmp(&priority_order(&b.priority))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    let total = affected.len();
 
-        server.mark_read(&contract.id);
+    let (start, mut offset) = match params.cursor.as_deref().map(parse_cursor) {
+        None => (0, 0),
+        Some(Ok((id, offset))) => match affected.iter().position(|(c, _)| c.id == id) {
+            Some(index) => (index, offset),
+            None => return super::error_response(format!(
+                "Invalid cursor: contract '{id}' is not affected by these files. Pass the same files as the previous call."
+            )),
+        },
+        Some(Err(e)) => return super::error_response(e),
+    };
 
-        entries.push(AffectedEntry {
+    let budget = params.max_tokens.map_or(usize::MAX, |t| t.saturating_mul(BYTES_PER_TOKEN));
+    let mut used = 0usize;
+    let mut reader = file_content::Reader::new(&server.config, server.root());
+    let mut entries = Vec::new();
+    let mut next_cursor = None;
+
+    for (contract, matched) in affected.into_iter().skip(start) {
+        let paths = ordered_files(&contract, &matched);
+        let id = contract.id.clone();
+        let mut entry = AffectedEntry {
             id: contract.id.clone(),
             version: contract.version.clone(),
             name: contract.name.clone(),
@@ -111,17 +138,179 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
             tags: contract.tags.clone(),
             trigger_type: contract.trigger.as_ref().and_then(|t| t.kind.clone()),
             matched_files: matched,
+            continued: offset > 0,
             contract,
-            file_contents,
-        });
+            file_contents: serde_json::Map::new(),
+        };
+        let cost = serde_json::to_string(&entry).map_or(0, |s| s.len());
+        if used.saturating_add(cost) > budget && !entries.is_empty() {
+            next_cursor = Some(cursor(&id, offset));
+            break;
+        }
+        used = used.saturating_add(cost);
+
+        let mut file_warnings = Vec::new();
+        let mut cut = None;
+        for (index, path) in paths.iter().enumerate().skip(offset) {
+            let first = entries.is_empty() && entry.file_contents.is_empty();
+            // Once not even the file's key fits, stop without reading the file.
+            let key_cost = path.len() + 4;
+            if used.saturating_add(key_cost) > budget && !first {
+                cut = Some(index);
+                break;
+            }
+            let resolved = reader.read(path);
+            let value = serde_json::to_value(&resolved).unwrap();
+            let cost = key_cost + value.to_string().len();
+            if used.saturating_add(cost) > budget && !first {
+                cut = Some(index);
+                break;
+            }
+            used = used.saturating_add(cost);
+            if let Some(warning) = resolved.warning(path) {
+                file_warnings.push(format!("Contract '{id}': {warning}"));
+            }
+            entry.file_contents.insert(path.clone(), value);
+        }
+        warnings.append(&mut file_warnings);
+        server.mark_read(&id);
+        entries.push(entry);
+        if let Some(index) = cut {
+            next_cursor = Some(cursor(&id, index));
+            break;
+        }
+        offset = 0;
     }
 
-    let total = entries.len();
     serde_json::to_string(&Response {
         affected_contracts: entries,
         total,
         diff: params.diff,
+        next_cursor,
         warnings,
     })
     .unwrap()
 }
+
+/// The contract's files with those in the changeset first, each listed once.
+fn ordered_files(contract: &Contract, matched: &[String]) -> Vec<String> {
+    let all = contract.all_files();
+    let mut ordered: Vec<String> = Vec::new();
+    let (first, rest): (Vec<&str>, Vec<&str>) = all.into_iter().partition(|f| matched.iter().any(|m| m == f));
+    for path in first.into_iter().chain(rest) {
+        if !ordered.iter().any(|p| p == path) {
+            ordered.push(path.to_string());
+        }
+    }
+    ordered
+}
+
+fn priority_order(priority: &Priority) -> u8 {
+    match priority {
+        Priority::Must => 0,
+        Priority::Should => 1,
+        Priority::Prefer => 2,
+    }
+}
+
+/// A cursor names the contract to resume at and the index of its next file.
+fn cursor(id: &str, offset: usize) -> String {
+    format!("{id}#{offset}")
+}
+
+fn parse_cursor(cursor: &str) -> Result<(&str, usize), String> {
+    cursor
+        .split_once('#')
+        .and_then(|(id, offset)| Some((id, offset.parse().ok()?)))
+        .ok_or_else(|| format!("Invalid cursor '{cursor}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::fs;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_review_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(dir: &std::path::Path, id: &str, priority: &str, files: &str) {
+        let content = format!(
+            "id = \"{id}\"\nversion = \"1.0.0\"\nname = \"{id}\"\ndescription = \"d\"\npriority = \"{priority}\"\napplies_to = [\"src/**\"]\nfiles = [{files}]\n"
+        );
+        fs::write(dir.join(format!("{id}.contract.toml")), content).unwrap();
+    }
+
+    fn setup(tag: &str) -> super::super::SigilServer {
+        let dir = temp_dir(tag);
+        write(&dir, "a-should", "should", "\"src/main.rs\"");
+        write(&dir, "b-must", "must", "\"src/config.rs\", \"src/model.rs\"");
+        super::super::SigilServer::new(Config {
+            contracts_dir: dir.to_str().unwrap().to_string(),
+            ..Config::default()
+        })
+    }
+
+    fn params(max_tokens: Option<usize>, cursor: Option<String>) -> Params {
+        Params { files: vec!["src/model.rs".to_string()], diff: None, max_tokens, cursor }
+    }
+
+    fn files_of(page: &serde_json::Value) -> Vec<(String, String)> {
+        let mut out = Vec::new();
+        for entry in page["affected_contracts"].as_array().unwrap() {
+            for path in entry["file_contents"].as_object().unwrap().keys() {
+                out.push((entry["id"].as_str().unwrap().to_string(), path.clone()));
+            }
+        }
+        out
+    }
+
+    fn expected() -> Vec<(String, String)> {
+        [("b-must", "src/model.rs"), ("b-must", "src/config.rs"), ("a-should", "src/main.rs")]
+            .iter()
+            .map(|(id, path)| (id.to_string(), path.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn orders_by_priority_and_matched_files_first() {
+        let server = setup("order");
+        let json: serde_json::Value = serde_json::from_str(&handle(&server, params(None, None)).await).unwrap();
+        assert_eq!(json["total"], 2);
+        assert!(json.get("next_cursor").is_none());
+        assert_eq!(files_of(&json), expected());
+    }
+
+    #[tokio::test]
+    async fn pages_through_everything_once_with_a_small_budget() {
+        let server = setup("pages");
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        let mut pages = 0;
+        loop {
+            let result = handle(&server, params(Some(1), cursor)).await;
+            let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+            assert_eq!(json["total"], 2, "{result}");
+            seen.extend(files_of(&json));
+            pages += 1;
+            match json["next_cursor"].as_str() {
+                Some(next) => cursor = Some(next.to_string()),
+                None => break,
+            }
+        }
+        assert_eq!(seen, expected());
+        assert_eq!(pages, 3);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_cursor() {
+        let server = setup("bad_cursor");
+        let result = handle(&server, params(Some(1), Some("nope#0".to_string()))).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json["error"].as_str().unwrap().contains("nope"));
+    }
+}
@@ -0,0 +1,204 @@
+use super::reviews::{self, Verdict, VerdictRecord};
+use crate::config::Enforcement;
+use crate::model::{Priority, Status};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {
+    /// Review session id whose verdicts should be aggregated.
+    pub review_id: String,
+    /// Branch the review runs on, for branch-specific gate overrides in sigil.config.toml.
+    pub branch: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    review_id: String,
+    pass: bool,
+    contracts: Vec<ContractSummary>,
+    counts: Counts,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize, Default)]
+struct Counts {
+    pass: usize,
+    fail: usize,
+    needs_human_review: usize,
+}
+
+#[derive(Serialize)]
+struct ContractSummary {
+    id: String,
+    priority: Priority,
+    status: Status,
+    /// Worst verdict recorded for the contract or any of its rules.
+    verdict: Verdict,
+    enforcement: Enforcement,
+    /// True when this contract's verdict makes the review fail.
+    blocking: bool,
+    verdicts: Vec<VerdictRecord>,
+}
+
+pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    let session = match reviews::load(&server.config.reviews_dir, &params.review_id) {
+        Ok(s) => s,
+        Err(e) => return super::error_response(e),
+    };
+    let (contracts, mut warnings) = super::loader::load_contracts(&server.config.contracts_dir);
+
+    let mut counts = Counts::default();
+    let mut summaries = Vec::new();
+
+    for contract in &contracts {
+        let verdicts: Vec<VerdictRecord> = session
+            .verdicts
+            .iter()
+            .filter(|v| v.contract_id == contract.id)
+            .cloned()
+            .collect();
+        let Some(verdict) = verdicts.iter().map(|v| v.verdict).max() else {
+            continue;
+        };
+
+        match verdict {
+            Verdict::Pass => counts.pass += 1,
+            Verdict::Fail => counts.fail += 1,
+            Verdict::NeedsHumanReview => counts.needs_human_review += 1,
+        }
+
+        let enforcement = server.config.gate.enforcement(
+            &contract.priority,
+            &contract.status,
+            params.branch.as_deref(),
+        );
+        summaries.push(ContractSummary {
+            id: contract.id.clone(),
+            priority: contract.priority.clone(),
+            status: contract.status.clone(),
+            verdict,
+            enforcement,
+            blocking: enforcement == Enforcement::Block && verdict != Verdict::Pass,
+            verdicts,
+        });
+    }
+
+    for v in &session.verdicts {
+        if !contracts.iter().any(|c| c.id == v.contract_id) {
+            warnings.push(format!(
+                "Verdict recorded for unknown contract '{}' was ignored",
+                v.contract_id
+            ));
+        }
+    }
+
+    let pass = !summaries.iter().any(|s| s.blocking);
+    serde_json::to_string(&Response {
+        review_id: session.review_id,
+        pass,
+        contracts: summaries,
+        counts,
+        warnings,
+    })
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::tools::record_verdict;
+    use std::fs;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_review_summary_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("contracts")).unwrap();
+        dir
+    }
+
+    fn write(dir: &std::path::Path, id: &str, priority: &str, status: &str) {
+        let content = format!(
+            "id = \"{id}\"\nversion = \"1.0.0\"\nname = \"{id}\"\ndescription = \"desc\"\npriority = \"{priority}\"\nstatus = \"{status}\"\n\n[[rules]]\nid = \"r1\"\ndescription = \"rule\"\n"
+        );
+        fs::write(dir.join("contracts").join(format!("{id}.contract.toml")), content).unwrap();
+    }
+
+    fn make_server(dir: &std::path::Path) -> super::super::SigilServer {
+        super::super::SigilServer::new(Config {
+            contracts_dir: dir.join("contracts").display().to_string(),
+            reviews_dir: dir.join("reviews").display().to_string(),
+            ..Config::default()
+        })
+    }
+
+    async fn record(server: &super::super::SigilServer, id: &str, rule: Option<&str>, verdict: Verdict) -> String {
+        server.mark_read(id);
+        record_verdict::handle(
+            server,
+            record_verdict::Params {
+                review_id: "pr-1".to_string(),
+                contract_id: id.to_string(),
+                rule_id: rule.map(str::to_string),
+                verdict,
+                rationale: "checked".to_string(),
+                evidence: None,
+            },
+        )
+        .await
+    }
+
+    async fn summary(server: &super::super::SigilServer) -> serde_json::Value {
+        let result = handle(server, Params { review_id: "pr-1".to_string(), branch: None }).await;
+        serde_json::from_str(&result).unwrap()
+    }
+
+    #[tokio::test]
+    async fn failing_must_contract_fails_review() {
+        let dir = temp_dir("must_fail");
+        write(&dir, "strict", "must", "active");
+        let server = make_server(&dir);
+        record(&server, "strict", None, Verdict::Pass).await;
+        record(&server, "strict", Some("r1"), Verdict::Fail).await;
+        let json = summary(&server).await;
+        assert_eq!(json["pass"], false);
+        assert_eq!(json["contracts"][0]["verdict"], "fail");
+        assert_eq!(json["counts"]["fail"], 1);
+    }
+
+    #[tokio::test]
+    async fn failing_should_draft_or_deprecated_contracts_do_not_block() {
+        let dir = temp_dir("non_blocking");
+        write(&dir, "soft", "should", "active");
+        write(&dir, "wip", "must", "draft");
+        write(&dir, "old", "must", "deprecated");
+        let server = make_server(&dir);
+        for id in ["soft", "wip", "old"] {
+            record(&server, id, None, Verdict::Fail).await;
+        }
+        let json = summary(&server).await;
+        assert_eq!(json["pass"], true, "{json}");
+        assert_eq!(json["counts"]["fail"], 3);
+    }
+
+    #[tokio::test]
+    async fn needs_human_review_on_must_blocks() {
+        let dir = temp_dir("human");
+        write(&dir, "strict", "must", "active");
+        let server = make_server(&dir);
+        record(&server, "strict", None, Verdict::NeedsHumanReview).await;
+        let json = summary(&server).await;
+        assert_eq!(json["pass"], false);
+    }
+
+    #[tokio::test]
+    async fn record_rejects_unknown_rule() {
+        let dir = temp_dir("unknown_rule");
+        write(&dir, "strict", "must", "active");
+        let server = make_server(&dir);
+        let result = record(&server, "strict", Some("nope"), Verdict::Pass).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json["error"].as_str().unwrap().contains("nope"));
+    }
+}
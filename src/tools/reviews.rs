@@ -0,0 +1,137 @@
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Pass,
+    NeedsHumanReview,
+    Fail,
+}
+
+/// A file (and optionally a line range) supporting a verdict.
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct Evidence {
+    /// File path relative to the project root.
+    pub file: String,
+    /// First line of the relevant region (1-based).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// Last line of the relevant region (1-based, inclusive).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VerdictRecord {
+    pub contract_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    pub verdict: Verdict,
+    pub rationale: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<Evidence>,
+    pub recorded_at: String,
+}
+
+/// All verdicts recorded under one review id, persisted as `<reviews_dir>/<review_id>.json`.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReviewSession {
+    pub review_id: String,
+    pub verdicts: Vec<VerdictRecord>,
+}
+
+impl ReviewSession {
+    /// Insert a verdict, replacing any earlier verdict for the same contract and rule.
+    /// Returns true if an earlier verdict was replaced.
+    pub fn record(&mut self, record: VerdictRecord) -> bool {
+        let existing = self
+            .verdicts
+            .iter()
+            .position(|v| v.contract_id == record.contract_id && v.rule_id == record.rule_id);
+        match existing {
+            Some(i) => {
+                self.verdicts[i] = record;
+                true
+            }
+            None => {
+                self.verdicts.push(record);
+                false
+            }
+        }
+    }
+}
+
+pub fn session_path(reviews_dir: &str, review_id: &str) -> Result<String, String> {
+    let valid = !review_id.is_empty()
+        && !review_id.starts_with('.')
+        && review_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!(
+            "Invalid review_id '{review_id}': use letters, digits, '-', '_' or '.', not starting with '.'"
+        ));
+    }
+    Ok(format!("{}/{review_id}.json", reviews_dir.trim_end_matches('/')))
+}
+
+/// Load a review session. A session that has not been written yet is empty.
+pub fn load(reviews_dir: &str, review_id: &str) -> Result<ReviewSession, String> {
+    let path = session_path(reviews_dir, review_id)?;
+    match std::fs::read_to_string(&path) {
+        Ok(s) => serde_json::from_str(&s).map_err(|e| format!("Failed to parse '{path}': {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ReviewSession {
+            review_id: review_id.to_string(),
+            verdicts: Vec::new(),
+        }),
+        Err(e) => Err(format!("Failed to read '{path}': {e}")),
+    }
+}
+
+pub fn save(reviews_dir: &str, session: &ReviewSession) -> Result<String, String> {
+    let path = session_path(reviews_dir, &session.review_id)?;
+    std::fs::create_dir_all(reviews_dir)
+        .and_then(|()| std::fs::write(&path, serde_json::to_string_pretty(session).unwrap()))
+        .map_err(|e| format!("Failed to write '{path}': {e}"))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(rule_id: Option<&str>, verdict: Verdict) -> VerdictRecord {
+        VerdictRecord {
+            contract_id: "c".to_string(),
+            rule_id: rule_id.map(str::to_string),
+            verdict,
+            rationale: "because".to_string(),
+            evidence: vec![],
+            recorded_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn record_replaces_same_contract_and_rule() {
+        let mut session = ReviewSession::default();
+        assert!(!session.record(record(Some("r1"), Verdict::Fail)));
+        assert!(!session.record(record(None, Verdict::Pass)));
+        assert!(session.record(record(Some("r1"), Verdict::Pass)));
+        assert_eq!(session.verdicts.len(), 2);
+        assert_eq!(session.verdicts[0].verdict, Verdict::Pass);
+    }
+
+    #[test]
+    fn session_path_rejects_traversal() {
+        assert!(session_path(".sigil/reviews/", "pr-42").is_ok());
+        assert!(session_path(".sigil/reviews/", "../escape").is_err());
+        assert!(session_path(".sigil/reviews/", "a/b").is_err());
+    }
+
+    #[test]
+    fn verdict_ordering_puts_fail_last() {
+        let worst = [Verdict::Pass, Verdict::Fail, Verdict::NeedsHumanReview].into_iter().max();
+        assert_eq!(worst, Some(Verdict::Fail));
+    }
+}
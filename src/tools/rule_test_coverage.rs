@@ -0,0 +1,297 @@
+use super::markers::{self, Marker};
+use crate::model::Status;
+use rmcp::schemars;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// A line that starts a test item: a Rust test attribute, a Python `def
+/// test_*` or a JavaScript/TypeScript `it(`/`test(` call.
+static TEST_ITEM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:#\[(?:[\w:]+::)?test\]|(?:async\s+)?def\s+test_|(?:it|test)\s*\()").unwrap()
+});
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct Params {
+    /// Limit the report to one contract. Omit to cover every contract.
+    pub contract_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    /// Rules with at least one valid test link or marker.
+    covered: usize,
+    total: usize,
+    rules: Vec<RuleCoverage>,
+    /// Rules with neither a valid `tests` entry nor a marker.
+    untested: Vec<RuleRef>,
+    /// `tests` entries whose file or test cannot be found.
+    broken_links: Vec<BrokenLink>,
+    /// Markers naming a contract or rule that does not exist.
+    unknown_markers: Vec<UnknownMarker>,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct RuleCoverage {
+    contract_id: String,
+    rule_id: String,
+    /// Valid entries of the rule's `tests` field.
+    tests: Vec<String>,
+    /// Markers naming this rule in test code, as `file:line`.
+    markers: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct RuleRef {
+    contract_id: String,
+    rule_id: String,
+}
+
+#[derive(Serialize)]
+struct BrokenLink {
+    contract_id: String,
+    rule_id: String,
+    test: String,
+    reason: String,
+}
+
+#[derive(Serialize)]
+struct UnknownMarker {
+    #[serde(flatten)]
+    marker: Marker,
+    reason: String,
+}
+
+pub async fn handle(server: &super::SigilServer, params: Params) -> String {
+    let (contracts, warnings) = super::loader::load_contracts(&server.config.contracts_dir);
+    if let Some(id) = &params.contract_id {
+        match super::loader::find_unique(&contracts, id) {
+            Ok(Some(_)) => {}
+            Ok(None) => return super::error_response(format!("Contract '{id}' not found")),
+            Err(e) => return super::error_response(e),
+        }
+    }
+
+    let all_markers = markers::scan(server.root(), &super::repo_files::list(server.root()), &server.config.contracts_dir);
+    let mut unknown_markers = Vec::new();
+    for marker in &all_markers {
+        if let Some(reason) = markers::unknown_reason(marker, &contracts)
+            && params.contract_id.as_ref().is_none_or(|id| id == &marker.contract_id)
+        {
+            unknown_markers.push(UnknownMarker { marker: marker.clone(), reason });
+        }
+    }
+
+    // Markers in production code bind code to contracts; only those in test
+    // code count as tests of a rule.
+    let mut test_code: HashMap<&str, Vec<bool>> = HashMap::new();
+    for marker in &all_markers {
+        test_code.entry(marker.file.as_str()).or_insert_with(|| {
+            super::file_content::read_to_string(server.root(), &marker.file)
+                .map(|text| test_lines(&marker.file, &text))
+                .unwrap_or_default()
+        });
+    }
+    let in_test_code = |m: &Marker| {
+        let lines = &test_code[m.file.as_str()];
+        [m.line, m.start_line].iter().any(|l| lines.get(l - 1).copied().unwrap_or(false))
+    };
+
+    let mut rules = Vec::new();
+    let mut untested = Vec::new();
+    let mut broken_links = Vec::new();
+    let selected = contracts
+        .iter()
+        .filter(|c| c.status != Status::Deprecated)
+        .filter(|c| params.contract_id.as_ref().is_none_or(|id| id == &c.id));
+    for contract in selected {
+        for rule in contract.rules.iter().flatten() {
+            let mut tests = Vec::new();
+            for test in rule.tests.iter().flatten() {
+                match check_link(server.root(), test) {
+                    Ok(()) => tests.push(test.clone()),
+                    Err(reason) => broken_links.push(BrokenLink {
+                        contract_id: contract.id.clone(),
+                        rule_id: rule.id.clone(),
+                        test: test.clone(),
+                        reason,
+                    }),
+                }
+            }
+            let found: Vec<String> = all_markers
+                .iter()
+                .filter(|m| m.contract_id == contract.id && m.rule_id.as_deref() == Some(rule.id.as_str()))
+                .filter(|m| in_test_code(m))
+                .map(|m| format!("{}:{}", m.file, m.line))
+                .collect();
+            if tests.is_empty() && found.is_empty() {
+                untested.push(RuleRef { contract_id: contract.id.clone(), rule_id: rule.id.clone() });
+            }
+            rules.push(RuleCoverage {
+                contract_id: contract.id.clone(),
+                rule_id: rule.id.clone(),
+                tests,
+                markers: found,
+            });
+        }
+    }
+
+    serde_json::to_string(&Response {
+        covered: rules.len() - untested.len(),
+        total: rules.len(),
+        rules,
+        untested,
+        broken_links,
+        unknown_markers,
+        warnings,
+    })
+    .unwrap()
+}
+
+/// Check a `tests` entry: `path` must exist inside the project, and with
+/// `path::name` the file must mention `name` as a whole identifier in test
+/// code, so `src/main.rs::main` is not a test.
+fn check_link(root: &Path, test: &str) -> Result<(), String> {
+    let (path, name) = match test.split_once("::") {
+        Some((path, name)) => (path, Some(name)),
+        None => (test, None),
+    };
+    let text = super::file_content::read_to_string(root, path)?;
+    let Some(name) = name else {
+        return Ok(());
+    };
+    let lines = test_lines(path, &text);
+    let found = text.lines().zip(&lines).any(|(line, &test)| test && mentions(line, name));
+    if found { Ok(()) } else { Err(format!("'{path}' has no test named '{name}'")) }
+}
+
+/// Whether `path` is a test file by the usual conventions: under a `tests`,
+/// `test` or `__tests__` directory, or named `test_*.py`, `*_test.py`,
+/// `*_test.go`, `*.test.*` or `*.spec.*`.
+fn is_test_file(path: &str) -> bool {
+    let mut components: Vec<&str> = path.split('/').collect();
+    let name = components.pop().unwrap_or_default();
+    components.iter().any(|c| matches!(*c, "tests" | "test" | "__tests__"))
+        || (name.starts_with("test_") && name.ends_with(".py"))
+        || name.ends_with("_test.py")
+        || name.ends_with("_test.go")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+/// For each line of `text`, whether it is test code: every line of a test
+/// file, the lines from a `#[cfg(test)]` module to the end of the file, and
+/// the head of each test item: a test attribute and the attributes, blank
+/// lines and comments after it, up to the line of the item itself.
+fn test_lines(path: &str, text: &str) -> Vec<bool> {
+    if is_test_file(path) {
+        return text.lines().map(|_| true).collect();
+    }
+    let mut in_test_module = false;
+    let mut in_head = false;
+    text.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            in_test_module |= trimmed.starts_with("#[cfg(test)]");
+            let test = in_test_module || in_head || TEST_ITEM_RE.is_match(line);
+            in_head = test
+                && !in_test_module
+                && (trimmed.is_empty() || trimmed.starts_with("#[") || trimmed.starts_with("//"));
+            test
+        })
+        .collect()
+}
+
+fn mentions(text: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(name).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + name.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::fs;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_coverage_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn reports_untested_rules_and_broken_links() {
+        let dir = temp_dir("links");
+        fs::write(
+            dir.join("c.contract.toml"),
+            r#"
+id = "c"
+version = "1.0.0"
+name = "C"
+description = "d"
+
+[[rules]]
+id = "linked"
+description = "d"
+tests = ["tests/api.rs::parses_orders"]
+
+[[rules]]
+id = "broken"
+description = "d"
+tests = ["src/main.rs::no_such_test", "src/main.rs::main", "tests/missing.rs"]
+
+[[rules]]
+id = "untested"
+description = "d"
+"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("tests")).unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.join("tests/api.rs"), "#[test]\nfn parses_orders() {}\n").unwrap();
+        let mut server = super::super::SigilServer::new(Config {
+            contracts_dir: dir.to_str().unwrap().to_string(),
+            ..Config::default()
+        });
+        server.root = dir.clone();
+        let result = handle(&server, Params { contract_id: None }).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["total"], 3, "{result}");
+        assert_eq!(json["covered"], 1);
+        let untested: Vec<&str> =
+            json["untested"].as_array().unwrap().iter().map(|r| r["rule_id"].as_str().unwrap()).collect();
+        assert_eq!(untested, vec!["broken", "untested"]);
+        assert_eq!(json["broken_links"].as_array().unwrap().len(), 3, "{result}");
+    }
+
+    #[test]
+    fn only_test_code_counts() {
+        let source = "fn main() {}\n\n#[test]\nfn parses() {}\n\nfn helper() {}\n\n\
+                      #[cfg(test)]\nmod tests {\n    fn later() {}\n}\n";
+        let lines = test_lines("src/lib.rs", source);
+        let tests: Vec<usize> = (0..lines.len()).filter(|&i| lines[i]).map(|i| i + 1).collect();
+        assert_eq!(tests, [3, 4, 8, 9, 10, 11]);
+        assert!(!lines[0], "main is production code");
+
+        assert!(test_lines("tests/api.rs", "fn main() {}").iter().all(|&t| t));
+        assert!(test_lines("web/cart.test.ts", "x").iter().all(|&t| t));
+        assert!(test_lines("py/test_cart.py", "x").iter().all(|&t| t));
+        let python = test_lines("py/cart.py", "def total():\n    pass\ndef test_total():\n    pass\n");
+        assert_eq!(python, [false, false, true, false]);
+    }
+
+    #[test]
+    fn test_names_match_whole_identifiers() {
+        assert!(mentions("fn parses_config() {}", "parses_config"));
+        assert!(!mentions("fn parses_config_twice() {}", "parses_config"));
+    }
+}
@@ -0,0 +1,272 @@
+use super::reviews::{ReviewSession, Verdict, VerdictRecord};
+use super::validation::{Issue, Report};
+use crate::model::Contract;
+use serde_json::{Value, json};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Validation issue kinds and the description of the SARIF rule each maps to.
+/// Kinds missing here still get a rule, described by the kind itself.
+const ISSUE_RULES: &[(&str, &str)] = &[
+    ("schema", "Contract does not conform to the contract JSON Schema"),
+    ("unknown_field", "Contract has a top-level field declared by no schema (strict mode)"),
+    ("trigger_schema", "Contract trigger does not conform to the schema registered for its type"),
+    ("missing_file", "Contract references a file that does not exist"),
+    ("unsafe_path", "Contract references a file outside the project root"),
+    ("duplicate_rule_id", "Contract defines the same rule id more than once"),
+    ("check_failed", "A deterministic rule check does not hold"),
+    ("unknown_annotation", "A sigil: annotation in source names an unknown contract or rule"),
+    ("invalid_check", "A rule check has an invalid regex, glob or path, or no files in scope"),
+    ("filename_mismatch", "Contract file name does not match its id"),
+    ("invalid_pattern", "Contract has an applies_to pattern that is not a valid glob"),
+    ("dead_pattern", "Contract has an applies_to pattern that matches no project file"),
+    ("load_warning", "Contract file could not be read or parsed"),
+    ("parse_error", "Contract buffer is not valid TOML"),
+    ("duplicate_contract_id", "Contract id is declared by more than one file"),
+    ("stray_file", "File looks like a contract but lacks the .contract.toml suffix"),
+    ("invalid_waivers_file", "Waivers file could not be read or parsed"),
+    ("unknown_waiver_contract", "Waiver references a contract that does not exist"),
+    ("unknown_waiver_rule", "Waiver references a rule that does not exist"),
+    ("invalid_waiver_path", "Waiver has an invalid path glob"),
+    ("expired_waiver", "Waiver is past its expiry date"),
+];
+
+/// Rules of the run, in the order they are first referenced.
+#[derive(Default)]
+struct Rules {
+    ids: Vec<String>,
+    definitions: Vec<Value>,
+}
+
+impl Rules {
+    fn index(&mut self, id: &str, description: impl FnOnce() -> String) -> usize {
+        if let Some(i) = self.ids.iter().position(|r| r == id) {
+            return i;
+        }
+        self.ids.push(id.to_string());
+        self.definitions.push(json!({
+            "id": id,
+            "shortDescription": { "text": description() },
+        }));
+        self.ids.len() - 1
+    }
+}
+
+/// Render validation issues and (optionally) the verdicts of a review session
+/// as a SARIF 2.1.0 log with a single run.
+pub fn render(contracts: &[Contract], validation: &Report, session: Option<&ReviewSession>) -> Value {
+    let mut rules = Rules::default();
+    let mut results = Vec::new();
+
+    for (level, issues) in [("error", &validation.errors), ("warning", &validation.warnings)] {
+        for issue in issues {
+            results.push(issue_result(&mut rules, issue, level));
+        }
+    }
+    for record in session.iter().flat_map(|s| &s.verdicts) {
+        if let Some(contract) = contracts.iter().find(|c| c.id == record.contract_id) {
+            results.push(verdict_result(&mut rules, contract, record));
+        }
+    }
+
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "sigil-mcp",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules.definitions,
+            }
+        },
+        "results": results,
+    });
+    if let Some(s) = session {
+        run["automationDetails"] = json!({ "id": format!("sigil/{}", s.review_id) });
+    }
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [run],
+    })
+}
+
+fn issue_result(rules: &mut Rules, issue: &Issue, level: &str) -> Value {
+    let rule_index = rules.index(issue.kind, || {
+        ISSUE_RULES
+            .iter()
+            .find(|(kind, _)| *kind == issue.kind)
+            .map_or_else(|| issue.kind.to_string(), |(_, d)| d.to_string())
+    });
+    let mut result = json!({
+        "ruleId": issue.kind,
+        "ruleIndex": rule_index,
+        "level": level,
+        "message": { "text": issue.message },
+    });
+    // Point at the file the issue was found in; a missing file cannot be opened.
+    if let Some(path) = &issue.source {
+        let region = issue.span.map(|s| {
+            json!({
+                "startLine": s.line,
+                "startColumn": s.column,
+                "endLine": s.end_line,
+                "endColumn": s.end_column,
+            })
+        });
+        result["locations"] = json!([location(path, region)]);
+    } else if let Some(path) = &issue.file {
+        result["locations"] = json!([location(path, None)]);
+    }
+    if let Some(id) = &issue.contract_id {
+        result["properties"] = json!({ "contractId": id });
+    }
+    result
+}
+
+fn verdict_result(rules: &mut Rules, contract: &Contract, record: &VerdictRecord) -> Value {
+    let rule = record
+        .rule_id
+        .as_ref()
+        .and_then(|id| contract.rules.iter().flatten().find(|r| &r.id == id));
+    let rule_id = match rule {
+        Some(r) => format!("contract/{}/{}", contract.id, r.id),
+        None => format!("contract/{}", contract.id),
+    };
+    let rule_index = rules.index(&rule_id, || match rule {
+        Some(r) => r.description.clone(),
+        None => contract.name.clone(),
+    });
+
+    let (level, kind) = match record.verdict {
+        Verdict::Fail => ("error", "fail"),
+        Verdict::NeedsHumanReview => ("warning", "review"),
+        Verdict::Pass => ("none", "pass"),
+    };
+    let mut locations: Vec<Value> = record
+        .evidence
+        .iter()
+        .map(|e| {
+            let region = e
+                .line
+                .map(|start| json!({ "startLine": start, "endLine": e.end_line.unwrap_or(start) }));
+            location(&e.file, region)
+        })
+        .collect();
+    if locations.is_empty()
+        && let Some(source) = &contract.source
+    {
+        locations.push(location(source, None));
+    }
+
+    json!({
+        "ruleId": rule_id,
+        "ruleIndex": rule_index,
+        "kind": kind,
+        "level": level,
+        "message": { "text": record.rationale },
+        "locations": locations,
+        "properties": {
+            "contractId": contract.id,
+            "priority": super::label(&contract.priority),
+            "verdict": super::label(&record.verdict),
+            "recordedAt": record.recorded_at,
+        },
+    })
+}
+
+fn location(path: &str, region: Option<Value>) -> Value {
+    let uri = path.replace('\\', "/");
+    let mut physical = json!({
+        "artifactLocation": { "uri": uri.trim_start_matches("./") },
+    });
+    if let Some(region) = region {
+        physical["region"] = region;
+    }
+    json!({ "physicalLocation": physical })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::locate::Span;
+    use crate::tools::reviews::Evidence;
+
+    fn contract() -> Contract {
+        let mut c: Contract = toml::from_str(
+            "id = \"no-pii\"\nversion = \"1.0.0\"\nname = \"No PII\"\ndescription = \"d\"\n\n[[rules]]\nid = \"logs\"\ndescription = \"No PII in logs\"\n",
+        )
+        .unwrap();
+        c.source = Some("contracts/no-pii.contract.toml".to_string());
+        c
+    }
+
+    fn issue(kind: &'static str) -> Issue {
+        Issue {
+            kind,
+            contract_id: Some("no-pii".to_string()),
+            message: "broken".to_string(),
+            file: Some("src/gone.rs".to_string()),
+            source: Some("contracts/no-pii.contract.toml".to_string()),
+            span: Some(Span { line: 3, column: 10, end_line: 3, end_column: 19 }),
+        }
+    }
+
+    fn verdict(rule_id: Option<&str>, verdict: Verdict, evidence: Vec<Evidence>) -> VerdictRecord {
+        VerdictRecord {
+            contract_id: "no-pii".to_string(),
+            rule_id: rule_id.map(str::to_string),
+            verdict,
+            rationale: "checked".to_string(),
+            evidence,
+            recorded_at: "2026-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn maps_issues_to_rules_and_contract_locations() {
+        let report = Report {
+            errors: vec![issue("missing_file")],
+            warnings: vec![issue("filename_mismatch")],
+            ..Report::default()
+        };
+        let log = render(&[contract()], &report, None);
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "missing_file");
+        assert_eq!(run["results"][0]["level"], "error");
+        assert_eq!(run["results"][1]["level"], "warning");
+        assert_eq!(run["results"][1]["ruleIndex"], 1);
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "contracts/no-pii.contract.toml"
+        );
+        let region = &run["results"][0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!((region["startLine"].as_u64(), region["startColumn"].as_u64()), (Some(3), Some(10)));
+    }
+
+    #[test]
+    fn maps_verdicts_to_results_with_evidence_regions() {
+        let evidence = vec![Evidence { file: "src/log.rs".to_string(), line: Some(4), end_line: Some(9) }];
+        let session = ReviewSession {
+            review_id: "pr-1".to_string(),
+            verdicts: vec![
+                verdict(Some("logs"), Verdict::Fail, evidence),
+                verdict(None, Verdict::Pass, vec![]),
+            ],
+        };
+        let log = render(&[contract()], &Report::default(), Some(&session));
+        let run = &log["runs"][0];
+        assert_eq!(run["automationDetails"]["id"], "sigil/pr-1");
+        let fail = &run["results"][0];
+        assert_eq!(fail["ruleId"], "contract/no-pii/logs");
+        assert_eq!(fail["level"], "error");
+        assert_eq!(fail["locations"][0]["physicalLocation"]["region"]["startLine"], 4);
+        let pass = &run["results"][1];
+        assert_eq!(pass["kind"], "pass");
+        assert_eq!(
+            pass["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "contracts/no-pii.contract.toml"
+        );
+        assert_eq!(run["tool"]["driver"]["rules"][0]["shortDescription"]["text"], "No PII in logs");
+    }
+}
@@ -0,0 +1,150 @@
+use crate::config::Config;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+const SCHEMA_STR: &str = include_str!("../../schema/contract.schema.json");
+
+/// The built-in contract schema.
+pub static SCHEMA: LazyLock<Value> = LazyLock::new(|| serde_json::from_str(SCHEMA_STR).unwrap());
+
+static VALIDATOR: LazyLock<jsonschema::Validator> =
+    LazyLock::new(|| jsonschema::validator_for(&SCHEMA).expect("contract schema is valid JSON Schema"));
+
+/// A way in which a contract, as JSON, breaks the schemas it must match.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    /// "schema" for the built-in schema and the project's extension,
+    /// "trigger_schema" for the schema of the trigger type, and
+    /// "unknown_field" for undeclared top-level fields in strict mode.
+    pub kind: &'static str,
+    pub pointer: String,
+    pub message: String,
+}
+
+/// Check a contract against the built-in schema, the configured schema
+/// extension, the schema of its trigger type and, in strict mode, the set of
+/// declared top-level fields.
+pub fn violations(config: &Config, contract: &Value) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = VALIDATOR
+        .iter_errors(contract)
+        .map(|e| schema_violation("schema", &e))
+        .collect();
+
+    if let Some(extension) = &config.schema.extension_schema {
+        let errors = extension.validator.iter_errors(contract);
+        violations.extend(errors.map(|e| schema_violation("schema", &e)));
+    }
+    let extension = config.schema.extension_schema.as_ref().map(|e| &e.schema);
+
+    violations.extend(
+        super::triggers::violations(config, contract)
+            .into_iter()
+            .map(|(pointer, message)| Violation { kind: "trigger_schema", pointer, message }),
+    );
+
+    if config.schema.strict
+        && let Some(fields) = contract.as_object()
+    {
+        for key in fields.keys().filter(|k| !declares(&SCHEMA, k) && !extension.is_some_and(|s| declares(s, k))) {
+            let message = match closest_field(key, extension) {
+                Some(known) => format!("Unknown field '{key}' (did you mean '{known}'?)"),
+                None => format!("Unknown field '{key}': declare it in the schema extension to allow it"),
+            };
+            violations.push(Violation { kind: "unknown_field", pointer: format!("/{key}"), message });
+        }
+    }
+    violations
+}
+
+fn schema_violation(kind: &'static str, error: &jsonschema::ValidationError) -> Violation {
+    let pointer = error.instance_path.to_string();
+    Violation { kind, message: format!("{error} at '{pointer}'"), pointer }
+}
+
+/// True if `schema` lists `key` under its top-level `properties`.
+fn declares(schema: &Value, key: &str) -> bool {
+    schema.get("properties").and_then(Value::as_object).is_some_and(|p| p.contains_key(key))
+}
+
+/// The declared field closest in spelling to `key`, if one is close enough
+/// to be a likely typo.
+fn closest_field(key: &str, extension: Option<&Value>) -> Option<String> {
+    [Some(&*SCHEMA), extension]
+        .into_iter()
+        .flatten()
+        .filter_map(|schema| schema.get("properties").and_then(Value::as_object))
+        .flat_map(|properties| properties.keys())
+        .map(|field| (distance(key, field), field))
+        .filter(|(d, _)| *d <= 2)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, field)| field.clone())
+}
+
+/// Levenshtein distance between two strings.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = (previous + usize::from(ca != *cb)).min(row[j] + 1).min(current + 1);
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ExtensionSchema;
+    use serde_json::json;
+
+    fn contract(extra: Value) -> Value {
+        let mut contract = json!({"id": "c", "version": "1.0.0", "name": "C", "description": "d"});
+        contract.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        contract
+    }
+
+    fn config(strict: bool) -> Config {
+        let mut config = Config::default();
+        config.schema.strict = strict;
+        let extension = json!({
+            "required": ["owner"],
+            "properties": {"owner": {"type": "string"}, "jira": {"type": "string", "pattern": "^[A-Z]+-[0-9]+$"}},
+        });
+        config.schema.extension_schema = Some(ExtensionSchema::compile(extension).unwrap());
+        config
+    }
+
+    #[test]
+    fn extension_is_checked_alongside_the_builtin_schema() {
+        let violations = violations(&config(false), &contract(json!({"jira": "nope", "priority": "high"})));
+        let pointers: Vec<&str> = violations.iter().map(|v| v.pointer.as_str()).collect();
+        assert_eq!(violations.len(), 3, "{violations:?}");
+        assert!(pointers.contains(&"/priority") && pointers.contains(&"/jira"), "{violations:?}");
+        assert!(violations.iter().any(|v| v.message.contains("owner")), "{violations:?}");
+        assert!(violations.iter().all(|v| v.kind == "schema"));
+
+        let valid = contract(json!({"owner": "team-a", "jira": "SIG-12", "custom": 1}));
+        assert!(super::violations(&config(false), &valid).is_empty(), "Undeclared fields pass when not strict");
+    }
+
+    #[test]
+    fn strict_mode_rejects_undeclared_fields_and_suggests_typos() {
+        let violations = violations(&config(true), &contract(json!({"owner": "a", "prority": "must", "ownr": "b", "sla": "x"})));
+        let messages: Vec<&str> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert!(violations.iter().all(|v| v.kind == "unknown_field"), "{violations:?}");
+        assert_eq!(
+            messages,
+            [
+                "Unknown field 'prority' (did you mean 'priority'?)",
+                "Unknown field 'ownr' (did you mean 'owner'?)",
+                "Unknown field 'sla': declare it in the schema extension to allow it",
+            ]
+        );
+        assert_eq!(violations[0].pointer, "/prority");
+    }
+}
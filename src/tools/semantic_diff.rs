@@ -0,0 +1,342 @@
+use crate::model::{Contract, Priority, Rule, Status};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Top-level fields that get a dedicated section in the diff instead of a
+/// generic old/new field change.
+const STRUCTURED_FIELDS: &[&str] = &["priority", "status", "applies_to", "rules"];
+
+/// Structured, field-level diff between two versions of a contract.
+#[derive(Debug, Serialize, Default)]
+pub struct ContractDiff {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Transition<Priority>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Transition<Status>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applies_to: Option<AppliesToChange>,
+    #[serde(skip_serializing_if = "RulesDiff::is_empty")]
+    pub rules: RulesDiff,
+}
+
+/// A plain field whose value changed. Absent values are represented as null.
+#[derive(Debug, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Transition<T> {
+    pub from: T,
+    pub to: T,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    Widened,
+    Narrowed,
+    Changed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AppliesToChange {
+    pub scope: Scope,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct RulesDiff {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub renamed: Vec<RuleRename>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changed: Vec<RuleChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Changes within a rule that kept its id (or was renamed, in which case `id` is the new id).
+#[derive(Debug, Serialize)]
+pub struct RuleChange {
+    pub id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldChange>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraints_added: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraints_removed: Vec<String>,
+}
+
+impl ContractDiff {
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+            && self.priority.is_none()
+            && self.status.is_none()
+            && self.applies_to.is_none()
+            && self.rules.is_empty()
+    }
+}
+
+impl RulesDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+impl RuleChange {
+    fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.constraints_added.is_empty() && self.constraints_removed.is_empty()
+    }
+}
+
+/// Compute the structured diff from `old` to `new`.
+pub fn diff(old: &Contract, new: &Contract) -> ContractDiff {
+    let fields = diff_fields(
+        &serde_json::to_value(old).unwrap(),
+        &serde_json::to_value(new).unwrap(),
+        STRUCTURED_FIELDS,
+    );
+
+    let priority = (old.priority != new.priority).then(|| Transition {
+        from: old.priority.clone(),
+        to: new.priority.clone(),
+    });
+    let status = (old.status != new.status).then(|| Transition {
+        from: old.status.clone(),
+        to: new.status.clone(),
+    });
+
+    ContractDiff {
+        fields,
+        priority,
+        status,
+        applies_to: diff_applies_to(&old.applies_to_patterns(), &new.applies_to_patterns()),
+        rules: diff_rules(
+            old.rules.as_deref().unwrap_or(&[]),
+            new.rules.as_deref().unwrap_or(&[]),
+        ),
+    }
+}
+
+/// Compare two JSON objects key by key, skipping `skip`. Keys keep the order
+/// they first appear in (old, then new-only keys).
+fn diff_fields(old: &serde_json::Value, new: &serde_json::Value, skip: &[&str]) -> Vec<FieldChange> {
+    let empty = serde_json::Map::new();
+    let old = old.as_object().unwrap_or(&empty);
+    let new = new.as_object().unwrap_or(&empty);
+
+    let mut keys: Vec<&String> = old.keys().collect();
+    keys.extend(new.keys().filter(|k| !old.contains_key(*k)));
+
+    keys.into_iter()
+        .filter(|k| !skip.contains(&k.as_str()))
+        .filter_map(|k| {
+            let o = old.get(k).cloned().unwrap_or(serde_json::Value::Null);
+            let n = new.get(k).cloned().unwrap_or(serde_json::Value::Null);
+            (o != n).then(|| FieldChange { field: k.clone(), old: o, new: n })
+        })
+        .collect()
+}
+
+fn diff_applies_to(old: &[&str], new: &[&str]) -> Option<AppliesToChange> {
+    let added: Vec<String> = new.iter().filter(|p| !old.contains(p)).map(|p| p.to_string()).collect();
+    let removed: Vec<String> = old.iter().filter(|p| !new.contains(p)).map(|p| p.to_string()).collect();
+
+    let scope = match (added.is_empty(), removed.is_empty()) {
+        (true, true) => return None,
+        (false, true) => Scope::Widened,
+        (true, false) => Scope::Narrowed,
+        // Replacing patterns is ambiguous unless one side is the global wildcard.
+        _ if new.contains(&"**") => Scope::Widened,
+        _ if old.contains(&"**") => Scope::Narrowed,
+        _ => Scope::Changed,
+    };
+    Some(AppliesToChange { scope, added, removed })
+}
+
+fn diff_rules(old: &[Rule], new: &[Rule]) -> RulesDiff {
+    let old_ids: HashSet<&str> = old.iter().map(|r| r.id.as_str()).collect();
+    let new_ids: HashSet<&str> = new.iter().map(|r| r.id.as_str()).collect();
+
+    let mut removed: Vec<&Rule> = old.iter().filter(|r| !new_ids.contains(r.id.as_str())).collect();
+    let mut added: Vec<&Rule> = new.iter().filter(|r| !old_ids.contains(r.id.as_str())).collect();
+
+    // A removed rule and an added rule with the same description are a rename.
+    let mut renamed = Vec::new();
+    let mut pairs = Vec::new();
+    removed.retain(|r| {
+        match added.iter().position(|a| a.description == r.description) {
+            Some(i) => {
+                let a = added.remove(i);
+                renamed.push(RuleRename { from: r.id.clone(), to: a.id.clone() });
+                pairs.push((*r, a));
+                false
+            }
+            None => true,
+        }
+    });
+
+    for o in old {
+        if let Some(n) = new.iter().find(|n| n.id == o.id) {
+            pairs.push((o, n));
+        }
+    }
+
+    let changed = pairs
+        .into_iter()
+        .map(|(o, n)| diff_rule(o, n))
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    RulesDiff {
+        added: added.into_iter().map(|r| r.id.clone()).collect(),
+        removed: removed.into_iter().map(|r| r.id.clone()).collect(),
+        renamed,
+        changed,
+    }
+}
+
+fn diff_rule(old: &Rule, new: &Rule) -> RuleChange {
+    let old_constraints = old.constraints.as_deref().unwrap_or(&[]);
+    let new_constraints = new.constraints.as_deref().unwrap_or(&[]);
+
+    RuleChange {
+        id: new.id.clone(),
+        fields: diff_fields(
+            &serde_json::to_value(old).unwrap(),
+            &serde_json::to_value(new).unwrap(),
+            &["id", "constraints"],
+        ),
+        constraints_added: new_constraints
+            .iter()
+            .filter(|c| !old_constraints.contains(c))
+            .cloned()
+            .collect(),
+        constraints_removed: old_constraints
+            .iter()
+            .filter(|c| !new_constraints.contains(c))
+            .cloned()
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::AppliesTo;
+
+    fn parse(toml_str: &str) -> Contract {
+        toml::from_str(toml_str).unwrap()
+    }
+
+    const BASE: &str = r#"
+id = "c"
+version = "1.0.0"
+name = "C"
+description = "desc"
+applies_to = "src/**"
+
+[[rules]]
+id = "r1"
+description = "first"
+constraints = ["a", "b"]
+
+[[rules]]
+id = "r2"
+description = "second"
+"#;
+
+    #[test]
+    fn identical_contracts_produce_empty_diff() {
+        let d = diff(&parse(BASE), &parse(BASE));
+        assert!(d.is_empty());
+    }
+
+    #[test]
+    fn reports_plain_field_changes_with_old_and_new() {
+        let old = parse(BASE);
+        let mut new = old.clone();
+        new.version = "1.1.0".to_string();
+        new.domain = Some("core".to_string());
+        let d = diff(&old, &new);
+        assert_eq!(d.fields.len(), 2);
+        assert_eq!(d.fields[0].field, "version");
+        assert_eq!(d.fields[0].old, "1.0.0");
+        assert_eq!(d.fields[1].field, "domain");
+        assert!(d.fields[1].old.is_null());
+    }
+
+    #[test]
+    fn reports_priority_and_status_transitions() {
+        let old = parse(BASE);
+        let mut new = old.clone();
+        new.priority = Priority::Prefer;
+        new.status = Status::Deprecated;
+        let d = diff(&old, &new);
+        assert_eq!(d.priority.unwrap().to, Priority::Prefer);
+        assert_eq!(d.status.unwrap().from, Status::Active);
+        assert!(d.fields.is_empty(), "priority/status must not be duplicated as field changes");
+    }
+
+    #[test]
+    fn classifies_applies_to_widening_and_narrowing() {
+        let old = parse(BASE);
+        let mut new = old.clone();
+        new.applies_to = Some(AppliesTo::Multiple(vec!["src/**".into(), "tests/**".into()]));
+        assert_eq!(diff(&old, &new).applies_to.unwrap().scope, Scope::Widened);
+
+        new.applies_to = None;
+        assert_eq!(diff(&old, &new).applies_to.unwrap().scope, Scope::Narrowed);
+    }
+
+    #[test]
+    fn reports_rule_additions_removals_and_constraint_changes() {
+        let old = parse(BASE);
+        let mut new = old.clone();
+        let rules = new.rules.as_mut().unwrap();
+        rules[0].constraints = Some(vec!["a".into(), "c".into()]);
+        rules.remove(1);
+        rules.push(Rule {
+            id: "r3".into(),
+            description: "third".into(),
+            files: None,
+            constraints: None,
+            checks: None,
+            tests: None,
+        });
+        let d = diff(&old, &new);
+        assert_eq!(d.rules.added, vec!["r3"]);
+        assert_eq!(d.rules.removed, vec!["r2"]);
+        assert_eq!(d.rules.changed.len(), 1);
+        assert_eq!(d.rules.changed[0].constraints_added, vec!["c"]);
+        assert_eq!(d.rules.changed[0].constraints_removed, vec!["b"]);
+    }
+
+    #[test]
+    fn detects_rule_rename_by_matching_description() {
+        let old = parse(BASE);
+        let mut new = old.clone();
+        new.rules.as_mut().unwrap()[1].id = "r2-renamed".into();
+        let d = diff(&old, &new);
+        assert!(d.rules.added.is_empty());
+        assert!(d.rules.removed.is_empty());
+        assert_eq!(d.rules.renamed[0].from, "r2");
+        assert_eq!(d.rules.renamed[0].to, "r2-renamed");
+    }
+}
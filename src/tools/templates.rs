@@ -0,0 +1,263 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// `{{name}}` in a string value, with optional spaces inside the braces.
+static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z0-9_-]+)\s*\}\}").unwrap());
+
+/// Table of a template file describing the template itself. It is dropped
+/// when the template is rendered.
+const META: &str = "template";
+
+const SUFFIX: &str = ".template.toml";
+
+/// A contract template: a contract file whose string values may contain
+/// `{{variable}}` placeholders, with an optional `[template]` table holding a
+/// `description` and a `variables` table of variable descriptions.
+#[derive(Debug)]
+pub struct Template {
+    pub name: String,
+    pub path: String,
+    pub description: Option<String>,
+    pub variables: Vec<Variable>,
+    body: toml::Table,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Variable {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Load all templates in the templates subdirectory of `dir`, sorted by name.
+/// The warnings cover template files that could not be read or parsed.
+pub fn load_templates(dir: &str) -> (Vec<Template>, Vec<String>) {
+    let templates_dir = Path::new(dir).join(super::loader::TEMPLATES_DIR);
+    let Ok(entries) = std::fs::read_dir(&templates_dir) else {
+        return (Vec::new(), Vec::new());
+    };
+    let mut paths: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .map(|e| e.path().display().to_string())
+        .filter(|p| p.ends_with(SUFFIX))
+        .collect();
+    paths.sort();
+
+    let mut templates = Vec::new();
+    let mut warnings = Vec::new();
+    for path in paths {
+        match load_template(&path) {
+            Ok(t) => templates.push(t),
+            Err(e) => warnings.push(format!("Failed to load template '{path}': {e}")),
+        }
+    }
+    (templates, warnings)
+}
+
+/// Load the template called `name`, i.e. `templates/<name>.template.toml`.
+/// Names must be kebab-case, like contract ids.
+pub fn find_template(dir: &str, name: &str) -> Result<Template, String> {
+    if !super::loader::is_valid_id(name) {
+        return Err(format!("Invalid template name '{name}': names must be kebab-case (e.g. 'kafka-consumer')"));
+    }
+    let path = format!("{}/{}/{name}{SUFFIX}", dir.trim_end_matches('/'), super::loader::TEMPLATES_DIR);
+    if !Path::new(&path).exists() {
+        let (templates, _) = load_templates(dir);
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        return Err(format!("Template '{name}' not found. Available templates: [{}]", names.join(", ")));
+    }
+    load_template(&path)
+}
+
+fn load_template(path: &str) -> Result<Template, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut body: toml::Table = content.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+    let name = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().trim_end_matches(SUFFIX).to_string())
+        .unwrap_or_default();
+
+    let mut description = None;
+    let mut variables = Vec::new();
+    if let Some(meta) = body.remove(META) {
+        let toml::Value::Table(meta) = meta else {
+            return Err(format!("'{META}' must be a table"));
+        };
+        description = meta.get("description").and_then(|v| v.as_str()).map(str::to_string);
+        if let Some(declared) = meta.get("variables") {
+            let Some(declared) = declared.as_table() else {
+                return Err(format!("'{META}.variables' must be a table of variable descriptions"));
+            };
+            for (var, desc) in declared {
+                let Some(desc) = desc.as_str() else {
+                    return Err(format!("Description of template variable '{var}' must be a string"));
+                };
+                variables.push(Variable { name: var.clone(), description: Some(desc.to_string()) });
+            }
+        }
+    }
+    // Placeholders that are not declared are still variables, just undocumented.
+    let mut used = Vec::new();
+    placeholders(&toml::Value::Table(body.clone()), &mut used);
+    for var in used {
+        if !variables.iter().any(|v| v.name == var) {
+            variables.push(Variable { name: var, description: None });
+        }
+    }
+
+    Ok(Template { name, path: path.to_string(), description, variables, body })
+}
+
+/// Names of the placeholders in the string values of `value`, in document
+/// order and without repeats.
+fn placeholders(value: &toml::Value, out: &mut Vec<String>) {
+    match value {
+        toml::Value::String(s) => {
+            for cap in PLACEHOLDER_RE.captures_iter(s) {
+                if !out.iter().any(|v| v == &cap[1]) {
+                    out.push(cap[1].to_string());
+                }
+            }
+        }
+        toml::Value::Array(items) => items.iter().for_each(|v| placeholders(v, out)),
+        toml::Value::Table(table) => table.values().for_each(|v| placeholders(v, out)),
+        _ => {}
+    }
+}
+
+impl Template {
+    /// The contract with every placeholder replaced by its value, as JSON
+    /// ready for schema validation. Values are substituted into parsed string
+    /// values, so they can never change the structure of the contract. Every
+    /// variable must be given, and no others.
+    pub fn render(&self, values: &BTreeMap<String, String>) -> Result<serde_json::Value, String> {
+        let missing: Vec<&str> = self
+            .variables
+            .iter()
+            .map(|v| v.name.as_str())
+            .filter(|name| !values.contains_key(*name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Template '{}' is missing variables: [{}]", self.name, missing.join(", ")));
+        }
+        let unknown: Vec<&str> = values
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !self.variables.iter().any(|v| v.name == *name))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!("Template '{}' has no variables: [{}]", self.name, unknown.join(", ")));
+        }
+
+        let mut body = toml::Value::Table(self.body.clone());
+        substitute(&mut body, values);
+        serde_json::to_value(&body).map_err(|e| e.to_string())
+    }
+}
+
+fn substitute(value: &mut toml::Value, values: &BTreeMap<String, String>) {
+    match value {
+        toml::Value::String(s) => {
+            *s = PLACEHOLDER_RE
+                .replace_all(s, |cap: &regex::Captures| values[&cap[1]].clone())
+                .into_owned();
+        }
+        toml::Value::Array(items) => items.iter_mut().for_each(|v| substitute(v, values)),
+        toml::Value::Table(table) => table.iter_mut().for_each(|(_, v)| substitute(v, values)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const TEMPLATE: &str = r#"
+id = "{{ id }}-consumer"
+version = "1.0.0"
+name = "{{topic}} consumer"
+description = "Consumes the {{topic}} topic."
+priority = "must"
+status = "active"
+files = ["src/consumers/{{ id }}.rs"]
+
+[[rules]]
+id = "idempotent"
+description = "Handling a {{topic}} message twice has no extra effect."
+
+[template]
+description = "A Kafka consumer"
+
+[template.variables]
+topic = "Topic consumed"
+"#;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigil_templates_test_{tag}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(super::super::loader::TEMPLATES_DIR)).unwrap();
+        dir
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn lists_declared_variables_first_then_placeholders() {
+        let dir = temp_dir("list");
+        fs::write(dir.join("templates/kafka-consumer.template.toml"), TEMPLATE).unwrap();
+        fs::write(dir.join("templates/broken.template.toml"), "id = ").unwrap();
+        fs::write(dir.join("templates/notes.md"), "not a template").unwrap();
+
+        let (templates, warnings) = load_templates(dir.to_str().unwrap());
+        assert_eq!(templates.len(), 1);
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        let template = &templates[0];
+        assert_eq!(template.name, "kafka-consumer");
+        assert_eq!(template.description.as_deref(), Some("A Kafka consumer"));
+        let names: Vec<&str> = template.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["topic", "id"]);
+        assert!(template.variables[1].description.is_none());
+    }
+
+    #[test]
+    fn renders_values_into_strings_only() {
+        let dir = temp_dir("render");
+        fs::write(dir.join("templates/kafka-consumer.template.toml"), TEMPLATE).unwrap();
+        let template = find_template(dir.to_str().unwrap(), "kafka-consumer").unwrap();
+
+        let contract = template.render(&vars(&[("id", "orders"), ("topic", "orders \"v2\"\n[x]")])).unwrap();
+        assert_eq!(contract["id"], "orders-consumer");
+        assert_eq!(contract["files"][0], "src/consumers/orders.rs");
+        assert_eq!(contract["name"], "orders \"v2\"\n[x] consumer");
+        assert!(contract.get("template").is_none());
+        assert!(contract.get("x").is_none());
+    }
+
+    #[test]
+    fn rejects_missing_and_unknown_variables_and_bad_names() {
+        let dir = temp_dir("errors");
+        fs::write(dir.join("templates/kafka-consumer.template.toml"), TEMPLATE).unwrap();
+        let dir = dir.to_str().unwrap();
+        let template = find_template(dir, "kafka-consumer").unwrap();
+
+        let err = template.render(&vars(&[("id", "orders")])).unwrap_err();
+        assert!(err.contains("missing variables: [topic]"), "{err}");
+        let err = template
+            .render(&vars(&[("id", "orders"), ("topic", "t"), ("team", "x")]))
+            .unwrap_err();
+        assert!(err.contains("no variables: [team]"), "{err}");
+
+        let err = find_template(dir, "../secrets").unwrap_err();
+        assert!(err.contains("Invalid template name"), "{err}");
+        let err = find_template(dir, "unknown").unwrap_err();
+        assert!(err.contains("[kafka-consumer]"), "{err}");
+    }
+}
@@ -0,0 +1,77 @@
+use crate::config::Config;
+
+/// Violations of the schema registered in `trigger_schemas` for the type of
+/// the contract's trigger, as (JSON pointer, message) pairs. `contract` is
+/// the contract as JSON. Contracts without a trigger, or whose trigger type
+/// has no schema, have none. Schemas are the ones compiled when the config
+/// was loaded.
+pub fn violations(config: &Config, contract: &serde_json::Value) -> Vec<(String, String)> {
+    let Some(trigger) = contract.get("trigger") else {
+        return Vec::new();
+    };
+    let Some(kind) = trigger.get("type").and_then(|t| t.as_str()) else {
+        return Vec::new();
+    };
+    let Some(validator) = config.trigger_validators.get(kind) else {
+        return Vec::new();
+    };
+    validator
+        .iter_errors(trigger)
+        .map(|e| {
+            let pointer = format!("/trigger{}", e.instance_path);
+            let message = format!("{e} at '{pointer}' (schema for trigger type '{kind}')");
+            (pointer, message)
+        })
+        .collect()
+}
+
+/// True if the trigger of `contract` has every field in `filter` with an
+/// equal value. The key `type` matches the trigger type.
+pub fn matches(contract: &crate::model::Contract, filter: &std::collections::BTreeMap<String, serde_json::Value>) -> bool {
+    let Some(trigger) = &contract.trigger else {
+        return filter.is_empty();
+    };
+    filter.iter().all(|(key, expected)| {
+        let actual = match key.as_str() {
+            "type" => trigger.kind.clone().map(serde_json::Value::String),
+            _ => trigger.extra.get(key).cloned(),
+        };
+        actual.as_ref() == Some(expected)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.trigger_schemas.insert(
+            "kafka-message".to_string(),
+            json!({
+                "type": "object",
+                "required": ["topic", "consumer_group"],
+                "properties": {"topic": {"type": "string"}},
+            }),
+        );
+        config.compile_trigger_schemas().unwrap();
+        config
+    }
+
+    #[test]
+    fn checks_triggers_of_registered_types_only() {
+        let config = config();
+        let missing = json!({"trigger": {"type": "kafka-message", "topic": 7}});
+        let violations = violations(&config, &missing);
+        let pointers: Vec<&str> = violations.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(pointers, ["/trigger", "/trigger/topic"], "{violations:?}");
+        assert!(violations[0].1.contains("consumer_group"), "{violations:?}");
+
+        let valid = json!({"trigger": {"type": "kafka-message", "topic": "t", "consumer_group": "g"}});
+        assert!(super::violations(&config, &valid).is_empty());
+        let unregistered = json!({"trigger": {"type": "http"}});
+        assert!(super::violations(&config, &unregistered).is_empty());
+        assert!(super::violations(&config, &json!({"id": "no-trigger"})).is_empty());
+    }
+}
@@ -1,9 +1,8 @@
+use super::semantic_diff::{self, ContractDiff};
+use crate::model::Contract;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
 use similar::{ChangeTag, TextDiff};
-use toml;
-
-const SCHEMA_STR: &str = include_str!("../../schema/contract.schema.json");
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct Params {
@@ -21,6 +20,8 @@ pub struct Params {
 struct Response {
     path: String,
     diff: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    semantic_diff: Option<ContractDiff>,
     warnings: Vec<String>,
 }
 
@@ -29,8 +30,10 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
         return e;
     }
 
-    let contracts_dir = server.config.contracts_dir.trim_end_matches('/');
-    let old_path = format!("{contracts_dir}/{}.contract.toml", params.contract_id);
+    let old_path = match super::loader::contract_path(&server.config.contracts_dir, &params.contract_id) {
+        Ok(p) => p,
+        Err(e) => return super::error_response(e),
+    };
 
     let old_yaml = match std::fs::read_to_string(&old_path) {
         Ok(s) => s,
@@ -42,6 +45,10 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
         }
         Err(e) => return super::error_response(format!("Failed to read '{old_path}': {e}")),
     };
+    let (contracts, _) = super::loader::load_contracts(&server.config.contracts_dir);
+    if let Err(e) = super::loader::find_unique(&contracts, &params.contract_id) {
+        return super::error_response(e);
+    }
 
     // Parse existing contract as JSON Value for merging
     let mut merged: serde_json::Value = match toml::from_str(&old_yaml) {
@@ -83,11 +90,9 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
     }
 
     // Schema validation
-    let schema_json: serde_json::Value = serde_json::from_str(SCHEMA_STR).unwrap();
-    let validator = jsonschema::validator_for(&schema_json).expect("contract schema is valid JSON Schema");
-    let schema_errors: Vec<String> = validator
-        .iter_errors(&merged)
-        .map(|e| format!("{} at '{}'", e, e.instance_path))
+    let schema_errors: Vec<String> = super::schema::violations(&server.config, &merged)
+        .into_iter()
+        .map(|v| v.message)
         .collect();
     if !schema_errors.is_empty() {
         return serde_json::json!({ "error": "Schema validation failed", "validation": schema_errors })
@@ -100,23 +105,41 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
         .and_then(|v| v.as_str())
         .unwrap_or(&params.contract_id)
         .to_string();
-    let new_path = format!("{contracts_dir}/{new_id}.contract.toml");
+    let new_path = match super::loader::contract_path(&server.config.contracts_dir, &new_id) {
+        Ok(p) => p,
+        Err(e) => return super::error_response(e),
+    };
 
-    // Check for id collision if id changed
-    if new_id != params.contract_id && std::path::Path::new(&new_path).exists() {
-        return super::error_response(format!(
-            "Cannot rename to '{new_id}': a contract with that id already exists at '{new_path}'."
-        ));
+    // Check for id collision if id changed, including contracts in subdirectories
+    if new_id != params.contract_id {
+        let existing = contracts
+            .iter()
+            .find(|c| c.id == new_id)
+            .and_then(|c| c.source.clone())
+            .or_else(|| std::path::Path::new(&new_path).exists().then(|| new_path.clone()));
+        if let Some(existing) = existing {
+            return super::error_response(format!(
+                "Cannot rename to '{new_id}': a contract with that id already exists at '{existing}'."
+            ));
+        }
     }
 
     // Serialize merged contract to TOML via typed struct to get consistent field order
-    let new_toml = match serde_json::from_value::<crate::model::Contract>(merged.clone())
-        .map_err(|e| e.to_string())
-        .and_then(|c| toml::to_string_pretty(&c).map_err(|e| e.to_string()))
-    {
+    let new_contract = match serde_json::from_value::<Contract>(merged) {
+        Ok(c) => c,
+        Err(e) => return super::error_response(format!("Failed to serialize contract: {e}")),
+    };
+    let unsafe_paths = super::file_content::unsafe_references(&new_contract);
+    if !unsafe_paths.is_empty() {
+        return serde_json::json!({ "error": "Unsafe file references", "validation": unsafe_paths })
+            .to_string();
+    }
+    let new_toml = match toml::to_string_pretty(&new_contract) {
         Ok(s) => s,
         Err(e) => return super::error_response(format!("Failed to serialize contract: {e}")),
     };
+    // Same layout as `sigil-mcp fmt`, so written contracts diff cleanly
+    let new_toml = super::format::format(&new_toml).unwrap_or(new_toml);
 
     // Write new file
     if let Err(e) = std::fs::write(&new_path, &new_toml) {
@@ -147,16 +170,25 @@ pub async fn handle(server: &super::SigilServer, params: Params) -> String {
         diff_text
     };
 
+    // Structured diff; omitted if the original no longer parses as a contract
+    let semantic_diff = toml::from_str::<Contract>(&old_yaml)
+        .ok()
+        .map(|old| semantic_diff::diff(&old, &new_contract));
+
     // Warn on missing files
     let mut warnings = Vec::new();
-    if let Ok(contract) = serde_json::from_value::<crate::model::Contract>(merged) {
-        for path in contract.all_files() {
-            if !std::path::Path::new(path).exists() {
-                warnings.push(format!("File does not exist: '{path}'"));
-            }
+    for path in new_contract.all_files() {
+        if !std::path::Path::new(path).exists() {
+            warnings.push(format!("File does not exist: '{path}'"));
         }
     }
 
-    serde_json::to_string(&Response { path: new_path, diff, warnings }).unwrap()
+    serde_json::to_string(&Response {
+        path: new_path,
+        diff,
+        semantic_diff,
+        warnings,
+    })
+    .unwrap()
 }
 
@@ -1,8 +1,6 @@
+use super::validation::{self, CheckResult, Issue, PatternMatches};
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-
-const SCHEMA_STR: &str = include_str!("../../schema/contract.schema.json");
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct Params {}
@@ -12,88 +10,23 @@ struct Response {
     pass: bool,
     errors: Vec<Issue>,
     warnings: Vec<Issue>,
-}
-
-#[derive(Serialize)]
-struct Issue {
-    kind: &'static str,
-    contract_id: Option<String>,
-    message: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    file: Option<String>,
+    /// Project files matched by each valid applies_to pattern.
+    patterns: Vec<PatternMatches>,
+    /// Outcome of every rule check, in contract and rule order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checks: Vec<CheckResult>,
 }
 
 pub async fn handle(server: &super::SigilServer, _params: Params) -> String {
-    let (contracts, load_warnings) = super::loader::load_contracts(&server.config.contracts_dir);
-
-    let mut errors: Vec<Issue> = Vec::new();
-    let mut warnings: Vec<Issue> = load_warnings
-        .into_iter()
-        .map(|m| Issue { kind: "load_warning", contract_id: None, message: m, file: None })
-        .collect();
-
-    let schema_json: serde_json::Value = serde_json::from_str(SCHEMA_STR).unwrap();
-    let validator = jsonschema::validator_for(&schema_json).expect("contract schema is valid JSON Schema");
-    let contracts_dir = server.config.contracts_dir.trim_end_matches('/');
-
-    for contract in &contracts {
-        let cid = Some(contract.id.clone());
-
-        // Schema validation
-        let contract_json = serde_json::to_value(contract).unwrap();
-        for error in validator.iter_errors(&contract_json) {
-            errors.push(Issue {
-                kind: "schema",
-                contract_id: cid.clone(),
-                message: format!("{} at '{}'", error, error.instance_path),
-                file: None,
-            });
-        }
-
-        // Missing files
-        for path in contract.all_files() {
-            if !std::path::Path::new(path).exists() {
-                errors.push(Issue {
-                    kind: "missing_file",
-                    contract_id: cid.clone(),
-                    message: format!("Referenced file does not exist: '{path}'"),
-                    file: Some(path.to_string()),
-                });
-            }
-        }
-
-        // Unique rule ids
-        if let Some(rules) = &contract.rules {
-            let mut seen = HashSet::new();
-            for b in rules {
-                if !seen.insert(b.id.as_str()) {
-                    errors.push(Issue {
-                        kind: "duplicate_rule_id",
-                        contract_id: cid.clone(),
-                        message: format!("Duplicate rule id: '{}'", b.id),
-                        file: None,
-                    });
-                }
-            }
-        }
-
-        // Filename-id consistency
-        let expected_path = format!("{contracts_dir}/{}.contract.toml", contract.id);
-        if !std::path::Path::new(&expected_path).exists() {
-            warnings.push(Issue {
-                kind: "filename_mismatch",
-                contract_id: cid.clone(),
-                message: format!(
-                    "Contract id '{}' has no matching file at '{expected_path}'",
-                    contract.id
-                ),
-                file: Some(expected_path),
-            });
-        }
-    }
-
-    let pass = errors.is_empty();
-    serde_json::to_string(&Response { pass, errors, warnings }).unwrap()
+    let report = validation::validate_all(&server.config, server.root());
+    serde_json::to_string(&Response {
+        pass: report.pass(),
+        errors: report.errors,
+        warnings: report.warnings,
+        patterns: report.patterns,
+        checks: report.checks,
+    })
+    .unwrap()
 }
 
 #[cfg(test)]
@@ -105,8 +38,7 @@ mod tests {
     fn make_server(contracts_dir: &str) -> super::super::SigilServer {
         super::super::SigilServer::new(Config {
             contracts_dir: contracts_dir.to_string(),
-            instructions: None,
-            notes: None,
+            ..Config::default()
         })
     }
 
@@ -215,4 +147,196 @@ files = ["does-not-exist.rs"]
         assert_eq!(json["pass"], false);
         assert!(!json["errors"].as_array().unwrap().is_empty());
     }
+
+    #[tokio::test]
+    async fn fail_on_expired_or_dangling_waivers() {
+        let dir = temp_dir("waivers");
+        write(&dir, "my-contract.contract.toml", r#"
+id = "my-contract"
+version = "1.0.0"
+name = "My Contract"
+description = "A contract"
+
+[[rules]]
+id = "rule-one"
+description = "first"
+"#);
+        write(&dir, "waivers.toml", r#"
+[[waivers]]
+contract_id = "my-contract"
+rule_id = "rule-one"
+reason = "still valid"
+owner = "platform"
+expires = "2999-01-01"
+
+[[waivers]]
+contract_id = "my-contract"
+reason = "ran out"
+owner = "platform"
+expires = "2000-01-01"
+
+[[waivers]]
+contract_id = "my-contract"
+rule_id = "rule-two"
+reason = "no such rule"
+owner = "platform"
+expires = "2999-01-01"
+
+[[waivers]]
+contract_id = "ghost"
+reason = "no such contract"
+owner = "platform"
+expires = "2999-01-01"
+"#);
+        let server = make_server(dir.to_str().unwrap());
+        let result = handle(&server, Params {}).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["pass"], false);
+        let kinds: Vec<&str> = json["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, ["expired_waiver", "unknown_waiver_rule", "unknown_waiver_contract"]);
+    }
+
+    #[tokio::test]
+    async fn issues_point_at_the_offending_key() {
+        let dir = temp_dir("spans");
+        write(&dir, "my-contract.contract.toml", r#"id = "my-contract"
+version = "one"
+name = "My Contract"
+description = "A contract"
+
+[[rules]]
+id = "rule-one"
+description = "first"
+files = ["nonexistent/path.rs"]
+"#);
+        let server = make_server(dir.to_str().unwrap());
+        let result = handle(&server, Params {}).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let errors = json["errors"].as_array().unwrap();
+        let span = |kind: &str| {
+            let e = errors.iter().find(|e| e["kind"] == kind).unwrap();
+            assert!(e["source"].as_str().unwrap().ends_with("my-contract.contract.toml"));
+            (e["span"]["line"].as_u64().unwrap(), e["span"]["column"].as_u64().unwrap())
+        };
+        assert_eq!(span("schema"), (2, 1));
+        assert_eq!(span("missing_file"), (9, 10));
+    }
+
+    #[tokio::test]
+    async fn fail_on_duplicate_contract_ids_and_warn_on_stray_files() {
+        let dir = temp_dir("duplicate_ids");
+        let contract = r#"
+id = "my-contract"
+version = "1.0.0"
+name = "My Contract"
+description = "A contract"
+"#;
+        write(&dir, "my-contract.contract.toml", contract);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        write(&dir.join("nested"), "copy.contract.toml", contract);
+        write(&dir, "other.toml", contract);
+        let server = make_server(dir.to_str().unwrap());
+        let result = handle(&server, Params {}).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["pass"], false);
+        let duplicates: Vec<_> = json["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|e| e["kind"] == "duplicate_contract_id")
+            .collect();
+        assert_eq!(duplicates.len(), 2);
+        assert!(duplicates[0]["message"].as_str().unwrap().contains("copy.contract.toml"));
+        let warnings = json["warnings"].as_array().unwrap();
+        assert!(warnings.iter().any(|w| w["kind"] == "stray_file"));
+    }
+
+    #[tokio::test]
+    async fn checks_applies_to_patterns_against_project_files() {
+        let dir = temp_dir("patterns");
+        write(&dir, "my-contract.contract.toml", r#"
+id = "my-contract"
+version = "1.0.0"
+name = "My Contract"
+description = "A contract"
+applies_to = ["src/**/*.rs", "renamed-away/**", "src/[oops"]
+"#);
+        let server = make_server(dir.to_str().unwrap());
+        let result = handle(&server, Params {}).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let errors = json["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0]["kind"], "invalid_pattern");
+        assert_eq!(errors[0]["span"]["line"], 6);
+        let warnings = json["warnings"].as_array().unwrap();
+        assert!(warnings.iter().any(|w| w["kind"] == "dead_pattern"
+            && w["message"].as_str().unwrap().contains("renamed-away/**")));
+        let patterns = json["patterns"].as_array().unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert!(patterns[0]["matched_files"].as_u64().unwrap() > 0);
+        assert_eq!(patterns[1]["matched_files"], 0);
+    }
+
+    #[tokio::test]
+    async fn fail_on_references_outside_the_project() {
+        let dir = temp_dir("unsafe_paths");
+        write(&dir, "my-contract.contract.toml", r#"
+id = "my-contract"
+version = "1.0.0"
+name = "My Contract"
+description = "A contract"
+files = ["/etc/passwd", "../outside.rs"]
+"#);
+        let server = make_server(dir.to_str().unwrap());
+        let result = handle(&server, Params {}).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let errors = json["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 2, "{result}");
+        assert!(errors.iter().all(|e| e["kind"] == "unsafe_path"));
+        assert_eq!(errors[1]["span"]["line"], 6);
+    }
+
+    #[tokio::test]
+    async fn evaluates_rule_checks() {
+        let dir = temp_dir("checks");
+        write(&dir, "my-contract.contract.toml", r#"
+id = "my-contract"
+version = "1.0.0"
+name = "My Contract"
+description = "A contract"
+
+[[rules]]
+id = "r1"
+description = "Model"
+files = ["src/model.rs"]
+
+[[rules.checks]]
+kind = "regex_must_match"
+pattern = 'pub struct Contract'
+
+[[rules.checks]]
+kind = "file_exists"
+path = "does/not/exist.rs"
+
+[[rules.checks]]
+kind = "regex_must_not_match"
+pattern = '('
+"#);
+        let server = make_server(dir.to_str().unwrap());
+        let result = handle(&server, Params {}).await;
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let checks = json["checks"].as_array().unwrap();
+        let passed: Vec<bool> = checks.iter().map(|c| c["passed"].as_bool().unwrap()).collect();
+        assert_eq!(passed, vec![true, false, false]);
+        let errors = json["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 2, "{result}");
+        assert_eq!(errors[0]["kind"], "check_failed");
+        assert_eq!(errors[0]["span"]["line"], 16);
+        assert_eq!(errors[1]["kind"], "invalid_check");
+    }
 }
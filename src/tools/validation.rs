@@ -0,0 +1,426 @@
+use super::loader::LoadError;
+use super::locate::{Locator, Span};
+use crate::config::Config;
+use crate::model::{AppliesTo, Contract, Waiver};
+use globset::Glob;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Issue {
+    pub kind: &'static str,
+    pub contract_id: Option<String>,
+    pub message: String,
+    /// File the issue is about, e.g. a referenced file that is missing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// File the issue was found in: a contract file or the waivers file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Where in `source` the issue is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct Report {
+    pub errors: Vec<Issue>,
+    pub warnings: Vec<Issue>,
+    /// How many project files each valid applies_to pattern matches.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<PatternMatches>,
+    /// Outcome of every rule check.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<CheckResult>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PatternMatches {
+    pub contract_id: String,
+    pub pattern: String,
+    pub matched_files: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CheckResult {
+    pub contract_id: String,
+    pub rule_id: String,
+    /// Position of the check in the rule's `checks` array.
+    pub index: usize,
+    pub kind: &'static str,
+    pub passed: bool,
+}
+
+impl Report {
+    pub fn pass(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// A file issues are found in, with its TOML document for resolving spans.
+struct Origin {
+    path: Option<String>,
+    locator: Option<Locator>,
+}
+
+impl Origin {
+    fn new(path: Option<&str>) -> Origin {
+        Origin {
+            path: path.map(str::to_string),
+            locator: path.and_then(Locator::from_file),
+        }
+    }
+
+    /// An origin whose spans are resolved against unsaved `text` instead of
+    /// the file on disk.
+    fn from_text(path: &str, text: &str) -> Origin {
+        Origin {
+            path: Some(path.to_string()),
+            locator: Locator::new(text),
+        }
+    }
+
+    fn issue(&self, kind: &'static str, contract_id: &str, pointer: &str, message: String) -> Issue {
+        Issue {
+            kind,
+            contract_id: Some(contract_id.to_string()),
+            message,
+            file: None,
+            source: self.path.clone(),
+            span: self.locator.as_ref().map(|l| l.span(pointer)),
+        }
+    }
+}
+
+/// Turn files that could not be loaded into issues of the given kind.
+pub fn load_issues(kind: &'static str, errors: Vec<LoadError>) -> Vec<Issue> {
+    errors
+        .into_iter()
+        .map(|e| Issue {
+            kind,
+            contract_id: None,
+            message: e.message,
+            file: None,
+            source: Some(e.path),
+            span: e.span,
+        })
+        .collect()
+}
+
+/// Validate every contract in the configured contracts directory, reading
+/// project files under `root`.
+pub fn validate_all(config: &Config, root: &Path) -> Report {
+    let (contracts, load_errors) = super::loader::load_contracts_detailed(&config.contracts_dir);
+    let mut report = Report {
+        errors: Vec::new(),
+        warnings: load_issues("load_warning", load_errors),
+        ..Report::default()
+    };
+    let repo_files = super::repo_files::list(root);
+    for contract in &contracts {
+        validate_contract(config, root, contract, &repo_files, &mut report);
+    }
+
+    // Ids declared by more than one file, reported once per file
+    for (id, paths) in super::loader::duplicate_ids(&contracts) {
+        for path in &paths {
+            let others: Vec<&str> = paths.iter().filter(|p| *p != path).map(String::as_str).collect();
+            let message = format!("Contract id '{id}' is also declared in {}", others.join(", "));
+            report
+                .errors
+                .push(Origin::new(Some(path)).issue("duplicate_contract_id", &id, "/id", message));
+        }
+    }
+
+    for path in super::loader::stray_contract_files(&config.contracts_dir) {
+        report.warnings.push(Issue {
+            kind: "stray_file",
+            contract_id: None,
+            message: format!("'{path}' looks like a contract but is not loaded: contract files must end in .contract.toml"),
+            file: None,
+            source: Some(path),
+            span: None,
+        });
+    }
+
+    // Source annotations and test markers must name real contracts and rules
+    let markers = super::markers::scan(root, &repo_files, &config.contracts_dir);
+    report.errors.extend(annotation_issues(markers, &contracts));
+
+    let (waivers, waiver_error) = super::loader::load_waivers(&config.contracts_dir);
+    report
+        .errors
+        .extend(load_issues("invalid_waivers_file", waiver_error.into_iter().collect()));
+    let waivers_file = format!(
+        "{}/{}",
+        config.contracts_dir.trim_end_matches('/'),
+        super::loader::WAIVERS_FILE
+    );
+    let origin = Origin::new(Some(&waivers_file));
+    let today = chrono::Local::now().date_naive();
+    for (index, waiver) in waivers.iter().enumerate() {
+        validate_waiver(waiver, index, &contracts, today, &origin, &mut report);
+    }
+    report
+}
+
+/// Errors for annotations and test markers naming an unknown contract, or
+/// an unknown rule of a known contract.
+pub fn annotation_issues(markers: Vec<super::markers::Marker>, contracts: &[Contract]) -> Vec<Issue> {
+    markers
+        .into_iter()
+        .filter_map(|marker| {
+            let reason = super::markers::unknown_reason(&marker, contracts)?;
+            Some(Issue {
+                kind: "unknown_annotation",
+                contract_id: Some(marker.contract_id.clone()),
+                message: format!("{reason} in annotation at {}:{}", marker.file, marker.line),
+                file: None,
+                source: Some(marker.file),
+                span: Some(marker.span),
+            })
+        })
+        .collect()
+}
+
+/// Check one waiver (the `index`-th in the waivers file) against the loaded
+/// contracts and today's date.
+fn validate_waiver(
+    waiver: &Waiver,
+    index: usize,
+    contracts: &[Contract],
+    today: NaiveDate,
+    origin: &Origin,
+    report: &mut Report,
+) {
+    let mut error = |kind: &'static str, field: &str, message: String| {
+        let pointer = format!("/waivers/{index}/{field}");
+        report.errors.push(origin.issue(kind, &waiver.contract_id, &pointer, message));
+    };
+
+    match contracts.iter().find(|c| c.id == waiver.contract_id) {
+        None => error(
+            "unknown_waiver_contract",
+            "contract_id",
+            format!("Waiver references unknown contract '{}'", waiver.contract_id),
+        ),
+        Some(contract) => {
+            if let Some(rule_id) = &waiver.rule_id {
+                let known = contract.rules.iter().flatten().any(|r| &r.id == rule_id);
+                if !known {
+                    error(
+                        "unknown_waiver_rule",
+                        "rule_id",
+                        format!(
+                            "Waiver references unknown rule '{rule_id}' in contract '{}'",
+                            waiver.contract_id
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    for (i, pattern) in waiver.paths.iter().flatten().enumerate() {
+        if let Err(e) = Glob::new(pattern) {
+            error(
+                "invalid_waiver_path",
+                &format!("paths/{i}"),
+                format!("Waiver for '{}' has invalid path pattern '{pattern}': {e}", waiver.contract_id),
+            );
+        }
+    }
+
+    if waiver.is_expired(today) {
+        error(
+            "expired_waiver",
+            "expires",
+            format!(
+                "Waiver for '{}' (owner {}) expired on {}: {}",
+                waiver.contract_id, waiver.owner, waiver.expires, waiver.reason
+            ),
+        );
+    }
+}
+
+/// Validate the contract in `text`, as if it were saved at `path`: an editor
+/// buffer that may differ from the file on disk. Besides the per-contract
+/// checks, reports TOML parse errors and ids declared by other contract files
+/// among `contracts`. The rules' checks are only evaluated with `run_rule_checks`.
+pub fn validate_text(
+    config: &Config,
+    root: &Path,
+    path: &str,
+    text: &str,
+    repo_files: &[String],
+    contracts: &[Contract],
+    run_rule_checks: bool,
+) -> Report {
+    let mut report = Report::default();
+    let mut contract: Contract = match toml::from_str(text) {
+        Ok(contract) => contract,
+        Err(e) => {
+            report.errors.push(Issue {
+                kind: "parse_error",
+                contract_id: None,
+                message: e.message().to_string(),
+                file: None,
+                source: Some(path.to_string()),
+                span: e.span().map(|range| Span::from_range(text, range)),
+            });
+            return report;
+        }
+    };
+    contract.source = Some(path.to_string());
+    let origin = Origin::from_text(path, text);
+    check_contract(config, &contract, &origin, repo_files, &mut report);
+    if run_rule_checks {
+        run_checks(root, &contract, &origin, repo_files, &mut report);
+    }
+
+    let normalize = |p: &str| p.trim_start_matches("./").to_string();
+    let elsewhere: Vec<String> = contracts
+        .iter()
+        .filter(|c| c.id == contract.id)
+        .filter_map(|c| c.source.as_deref().map(normalize))
+        .filter(|source| *source != normalize(path))
+        .collect();
+    if !elsewhere.is_empty() {
+        let message = format!("Contract id '{}' is also declared in {}", contract.id, elsewhere.join(", "));
+        report.errors.push(origin.issue("duplicate_contract_id", &contract.id, "/id", message));
+    }
+    report
+}
+
+/// Run every structural check on one contract, appending issues to `report`.
+/// `repo_files` lists the project's files under `root`, for finding dead
+/// applies_to patterns.
+pub fn validate_contract(
+    config: &Config,
+    root: &Path,
+    contract: &Contract,
+    repo_files: &[String],
+    report: &mut Report,
+) {
+    let origin = Origin::new(contract.source.as_deref());
+    check_contract(config, contract, &origin, repo_files, report);
+    run_checks(root, contract, &origin, repo_files, report);
+}
+
+fn check_contract(config: &Config, contract: &Contract, origin: &Origin, repo_files: &[String], report: &mut Report) {
+    let id = contract.id.as_str();
+
+    // Schema validation
+    let contract_json = serde_json::to_value(contract).unwrap();
+    for violation in super::schema::violations(config, &contract_json) {
+        let issue = origin.issue(violation.kind, id, &violation.pointer, violation.message);
+        report.errors.push(issue);
+    }
+
+    // Unsafe and missing files, located at the reference in the contract
+    let top_level = contract.files.iter().flatten().enumerate().map(|(i, f)| (format!("/files/{i}"), f));
+    let in_rules = contract.rules.iter().flatten().enumerate().flat_map(|(i, rule)| {
+        rule.files
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(move |(j, f)| (format!("/rules/{i}/files/{j}"), f))
+    });
+    for (pointer, path) in top_level.chain(in_rules) {
+        if let Err(message) = super::file_content::check_reference(path) {
+            let mut issue = origin.issue("unsafe_path", id, &pointer, message);
+            issue.file = Some(path.to_string());
+            report.errors.push(issue);
+        } else if !std::path::Path::new(path).exists() {
+            let message = format!("Referenced file does not exist: '{path}'");
+            let mut issue = origin.issue("missing_file", id, &pointer, message);
+            issue.file = Some(path.to_string());
+            report.errors.push(issue);
+        }
+    }
+
+    // applies_to patterns must compile and should match something
+    let patterns = contract.applies_to_patterns();
+    for (i, pattern) in patterns.iter().enumerate() {
+        let pointer = match contract.applies_to {
+            Some(AppliesTo::Multiple(_)) => format!("/applies_to/{i}"),
+            _ => "/applies_to".to_string(),
+        };
+        let matcher = match Glob::new(pattern) {
+            Ok(glob) => glob.compile_matcher(),
+            Err(e) => {
+                let message = format!("Invalid applies_to pattern '{pattern}': {e}");
+                report.errors.push(origin.issue("invalid_pattern", id, &pointer, message));
+                continue;
+            }
+        };
+        let matched_files = repo_files.iter().filter(|f| matcher.is_match(f.as_str())).count();
+        if matched_files == 0 {
+            let message = format!("applies_to pattern '{pattern}' matches no file in the project");
+            report.warnings.push(origin.issue("dead_pattern", id, &pointer, message));
+        }
+        report.patterns.push(PatternMatches {
+            contract_id: contract.id.clone(),
+            pattern: pattern.to_string(),
+            matched_files,
+        });
+    }
+
+    // Unique rule ids
+    if let Some(rules) = &contract.rules {
+        let mut seen = HashSet::new();
+        for (i, b) in rules.iter().enumerate() {
+            if !seen.insert(b.id.as_str()) {
+                let message = format!("Duplicate rule id: '{}'", b.id);
+                report
+                    .errors
+                    .push(origin.issue("duplicate_rule_id", id, &format!("/rules/{i}/id"), message));
+            }
+        }
+    }
+
+    // Filename-id consistency
+    let contracts_dir = config.contracts_dir.trim_end_matches('/');
+    let expected_path = format!("{contracts_dir}/{}.contract.toml", contract.id);
+    if !std::path::Path::new(&expected_path).exists() {
+        let message = format!("Contract id '{id}' has no matching file at '{expected_path}'");
+        let mut issue = origin.issue("filename_mismatch", id, "/id", message);
+        issue.file = Some(expected_path);
+        report.warnings.push(issue);
+    }
+}
+
+/// Evaluate the deterministic checks of every rule: failures are errors,
+/// unusable checks too. Checks read project files under `root`, so the
+/// language server only runs them when a document is opened or saved.
+fn run_checks(root: &Path, contract: &Contract, origin: &Origin, repo_files: &[String], report: &mut Report) {
+    let id = contract.id.as_str();
+    for (i, rule) in contract.rules.iter().flatten().enumerate() {
+        for (j, check) in rule.checks.iter().flatten().enumerate() {
+            let pointer = format!("/rules/{i}/checks/{j}");
+            let passed = match super::checks::evaluate(root, check, contract, rule, repo_files) {
+                Ok(failures) => {
+                    for failure in &failures {
+                        let message = format!("Rule '{}' check {j} ({}) failed: {failure}", rule.id, check.kind());
+                        report.errors.push(origin.issue("check_failed", id, &pointer, message));
+                    }
+                    failures.is_empty()
+                }
+                Err(e) => {
+                    let message = format!("Rule '{}' check {j} ({}) is invalid: {e}", rule.id, check.kind());
+                    report.errors.push(origin.issue("invalid_check", id, &pointer, message));
+                    false
+                }
+            };
+            report.checks.push(CheckResult {
+                contract_id: contract.id.clone(),
+                rule_id: rule.id.clone(),
+                index: j,
+                kind: check.kind(),
+                passed,
+            });
+        }
+    }
+}